@@ -0,0 +1,9 @@
+// The episode 2 bot, as submitted to CodinGame: reads the game on stdin, writes the jumps.
+
+use line_reader::{read_or_exit, LineReader};
+use shadow_of_the_knight::ep2;
+use std::io;
+
+fn main() {
+    read_or_exit(ep2::run(LineReader::stdin(), &mut io::stdout()));
+}
@@ -0,0 +1,127 @@
+// Plays the bot of an episode against a bomb on this machine, without going through CodinGame:
+//     cargo run --bin simulator -- --episode 2 10000 10000 31 9999 0 4321 1234
+// for a building of 10000x10000 windows, 31 jumps from (9999, 0) to the bomb on (4321, 1234),
+// prints the hint and jump of every turn, and whether the bomb was found within the jumps.
+// `--adversary` in place of the bomb plays against a bomb dodging the jumps, for the worst case.
+// `--sweep` in place of the bomb plays against every bomb of the building and prints the worst one.
+// The episode is 1 by default. The exit code is 1 when a bomb is not found.
+
+use shadow_of_the_knight::{
+    protocol::Init,
+    referee::{inside, play, sweep, Bomb, Episode},
+};
+use std::{env, process};
+
+const USAGE: &str = "usage: simulator [--episode <1|2>] [--sweep | --adversary] \
+    <width> <height> <jumps> <x0> <y0> [<bomb x> <bomb y>]";
+
+struct Options {
+    episode: Episode,
+    init: Init,
+    // None for the sweep
+    bomb: Option<Bomb>,
+}
+
+fn parse_args(args: Vec<String>) -> Option<Options> {
+    let mut episode = Episode::One;
+    let (mut sweep, mut adversary) = (false, false);
+    let mut values = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sweep" => sweep = true,
+            "--adversary" => adversary = true,
+            "--episode" => {
+                episode = match args.next()?.as_str() {
+                    "1" => Episode::One,
+                    "2" => Episode::Two,
+                    _ => return None,
+                }
+            }
+            _ => values.push(arg.parse::<i32>().ok()?),
+        }
+    }
+    let (init, bomb) = match (sweep, adversary, values.as_slice()) {
+        (true, false, &[width, height, jumps, x0, y0]) => ((width, height, jumps, x0, y0), None),
+        (false, true, &[width, height, jumps, x0, y0]) => {
+            ((width, height, jumps, x0, y0), Some(Bomb::Adversary))
+        }
+        (false, false, &[width, height, jumps, x0, y0, x, y]) => {
+            ((width, height, jumps, x0, y0), Some(Bomb::At((x, y))))
+        }
+        _ => return None,
+    };
+    let (width, height, jumps, x0, y0) = init;
+    let init = Init {
+        width,
+        height,
+        jumps,
+        x0,
+        y0,
+    };
+    let valid = width > 0
+        && height > 0
+        && (width, height) != (1, 1)
+        && inside(&init, (x0, y0))
+        && match bomb {
+            Some(Bomb::At(bomb)) => inside(&init, bomb) && bomb != (x0, y0),
+            _ => true,
+        };
+    valid.then_some(Options {
+        episode,
+        init,
+        bomb,
+    })
+}
+
+fn main() {
+    let Some(options) = parse_args(env::args().skip(1).collect()) else {
+        eprintln!("{USAGE}");
+        eprintln!("the start and the bomb are two different windows of the building");
+        process::exit(2);
+    };
+    let (episode, init) = (options.episode, &options.init);
+    let found = match options.bomb {
+        Some(bomb) => play_game(episode, init, bomb),
+        None => sweep_bombs(episode, init),
+    };
+    if !found {
+        process::exit(1);
+    }
+}
+
+fn play_game(episode: Episode, init: &Init, bomb: Bomb) -> bool {
+    let game = play(episode, init, bomb);
+    for (turn, played) in game.turns.iter().enumerate() {
+        let (x, y) = played.jump;
+        println!("turn {:>2}: {:<7} -> {x} {y}", turn + 1, played.hint);
+    }
+    let jumps = game.turns.len();
+    let bomb = match bomb {
+        Bomb::At(bomb) => format!("bomb {bomb:?}"),
+        Bomb::Adversary if game.found => format!("adversary bomb {:?}", game.turns[jumps - 1].jump),
+        Bomb::Adversary => "adversary bomb".to_string(),
+    };
+    if game.found {
+        println!("{bomb} found in {jumps} jumps of {}", init.jumps);
+    } else {
+        println!("{bomb} not found after {jumps} jumps of {}", init.jumps);
+    }
+    game.found
+}
+
+fn sweep_bombs(episode: Episode, init: &Init) -> bool {
+    let sweep = sweep(episode, init);
+    for bomb in &sweep.missed {
+        println!("bomb {bomb:?} not found in {} jumps", init.jumps);
+    }
+    println!(
+        "{} bombs, {} found, worst case {} jumps of {} for bomb {:?}",
+        sweep.games,
+        sweep.games - sweep.missed.len(),
+        sweep.worst_jumps,
+        init.jumps,
+        sweep.worst_bomb
+    );
+    sweep.missed.is_empty()
+}
@@ -1,80 +1,385 @@
 // ! Skills : Binary Search, Trilateration
 
-use std::io;
-
-macro_rules! parse_input {
-    ($x:expr, $t:ident) => ($x.trim().parse::<$t>().unwrap())
-}
+use crate::{
+    hints::ThermalHint,
+    protocol::{read_init, read_thermal_hint, write_jump, Init},
+    search_area::SearchArea,
+};
+use line_reader::{LineReader, ParseError};
+use std::{
+    cmp::Ordering,
+    io::{BufRead, Write},
+};
 
 // ! Skills: Binary Search in 2D and Intervals 
 
+/// Batman in episode 2: jumps splitting the windows left by the WARMER, COLDER and SAME hints.
 #[derive(Default, Debug, Clone)]
-pub struct SearchArea {
-    left_col: i32,
-    right_col: i32,
-    top_row: i32,
-    bottom_row: i32,
-}
-
-#[derive(Default, Debug, Clone)]
-pub struct Player {
+pub struct Episode2Solver {
     pos_x: i32,
     pos_y: i32,
+    // window before the last jump, none on the first turn
+    prev: Option<(i32, i32)>,
+    width: i32,
+    height: i32,
     jumps_left: i32,
-    search_area: SearchArea,
+    region: Region,
+}
+
+/// Windows the bomb can still be in: a column interval on each row from `top_row`,
+/// the cuts being half-planes, without the windows Batman stood on.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Region {
+    top_row: i32,
+    // (left, right) of each row, left > right for a row without a window
+    spans: Vec<(i32, i32)>,
+    // windows Batman stood on strictly inside a span, the ends of a span being cut off
+    holes: Vec<(i32, i32)>,
 }
 
 // Jumps required: log2(W*H)
+// The hints cut the building along the bisector of the last two windows. The region
+// follows the cuts exactly; the rectangle around it estimates the turn after.
 
-impl SearchArea {
-    fn new(width: i32, height: i32) -> Self {
+/// Windows of `area` whose distance to `current` compares to their distance to `previous`
+/// as the hint says: Less for WARMER, Greater for COLDER, Equal for SAME.
+/// None when no window is left. Only a row or a column bisector cuts a rectangle.
+fn cut_area(
+    area: &SearchArea,
+    hint: Ordering,
+    previous: (i32, i32),
+    current: (i32, i32),
+) -> Option<SearchArea> {
+    let ((qx, qy), (px, py)) = (previous, current);
+    let (qx, qy, px, py) = (qx as i64, qy as i64, px as i64, py as i64);
+    // dist(current)² - dist(previous)² = ax * x + ay * y + c
+    let (ax, ay) = (2 * (qx - px), 2 * (qy - py));
+    let c = px * px + py * py - qx * qx - qy * qy;
+    let mut kept = area.clone();
+    if ay == 0 || area.top_row == area.bottom_row {
+        let c = c + ay * area.top_row as i64;
+        let (left, right) = keep(hint, ax, c, area.left_col, area.right_col)?;
+        kept.set_columns(left, right);
+    } else if ax == 0 || area.left_col == area.right_col {
+        let c = c + ax * area.left_col as i64;
+        let (top, bottom) = keep(hint, ay, c, area.top_row, area.bottom_row)?;
+        kept.set_rows(top, bottom);
+    }
+    // a diagonal bisector across several rows and columns leaves the area whole
+    Some(kept)
+}
+
+/// Windows of `area` left by the worst hint on a jump from `from` to `to`.
+fn worst_split(area: &SearchArea, from: (i32, i32), to: (i32, i32)) -> i64 {
+    [Ordering::Less, Ordering::Greater, Ordering::Equal]
+        .into_iter()
+        .filter_map(|hint| cut_area(area, hint, from, to))
+        .map(|kept| kept.windows())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Values v of lo..=hi where `a * v + b` compares to 0 as `hint`, None if none does.
+fn keep(hint: Ordering, a: i64, b: i64, lo: i32, hi: i32) -> Option<(i32, i32)> {
+    let (lo, hi) = (lo as i64, hi as i64);
+    let (from, to) = match (hint, a.cmp(&0)) {
+        (_, Ordering::Equal) if b.cmp(&0) == hint => (lo, hi),
+        (_, Ordering::Equal) => return None,
+        (Ordering::Equal, _) if b % a == 0 => (-b / a, -b / a),
+        (Ordering::Equal, _) => return None,
+        // a * v + b < 0
+        (Ordering::Less, Ordering::Greater) => (lo, (-b - 1).div_euclid(a)),
+        (Ordering::Less, Ordering::Less) => (b.div_euclid(-a) + 1, hi),
+        // a * v + b > 0
+        (Ordering::Greater, Ordering::Greater) => ((-b).div_euclid(a) + 1, hi),
+        (Ordering::Greater, Ordering::Less) => (lo, (b - 1).div_euclid(-a)),
+    };
+    let (from, to) = (from.max(lo), to.min(hi));
+    (from <= to).then_some((from as i32, to as i32))
+}
+
+impl Region {
+    pub(crate) fn new(width: i32, height: i32) -> Self {
         Self {
-            left_col: 0,
-            right_col: width-1,
             top_row: 0,
-            bottom_row: height-1,
+            spans: vec![(0, width - 1); height as usize],
+            holes: Vec::new(),
         }
     }
 
-    fn set_rows(&mut self, top: i32, bottom: i32) {
-        self.top_row = top;
-        self.bottom_row = bottom;
+    // drops the holes outside the spans, cuts the holes off the ends of the spans
+    // and the rows without a window off the top and bottom, None when no window is left
+    fn trimmed(top_row: i32, mut spans: Vec<(i32, i32)>, holes: &[(i32, i32)]) -> Option<Self> {
+        let is_hole = |col: i32, row: i32| holes.contains(&(col, row));
+        for (span, row) in spans.iter_mut().zip(top_row..) {
+            while span.0 <= span.1 && is_hole(span.0, row) {
+                span.0 += 1;
+            }
+            while span.0 <= span.1 && is_hole(span.1, row) {
+                span.1 -= 1;
+            }
+        }
+        let first = spans.iter().position(|(left, right)| left <= right)?;
+        let last = spans.iter().rposition(|(left, right)| left <= right)?;
+        let mut region = Region {
+            top_row: top_row + first as i32,
+            spans: spans[first..=last].to_vec(),
+            holes: Vec::new(),
+        };
+        region.holes = holes.iter().copied().filter(|&hole| region.in_span(hole)).collect();
+        Some(region)
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (i32, i32, i32)> + '_ {
+        let top = self.top_row;
+        let spans = self.spans.iter().enumerate();
+        spans
+            .filter(|(_, (left, right))| left <= right)
+            .map(move |(idx, &(left, right))| (top + idx as i32, left, right))
+    }
+
+    pub(crate) fn windows(&self) -> i64 {
+        let spans: i64 = self.rows().map(|(_, left, right)| (right - left + 1) as i64).sum();
+        spans - self.holes.len() as i64
     }
 
-    fn set_columns(&mut self, left: i32, right: i32) {
-        self.left_col = left;
-        self.right_col = right;
+    fn in_span(&self, (x, y): (i32, i32)) -> bool {
+        let idx = y - self.top_row;
+        idx >= 0
+            && self
+                .spans
+                .get(idx as usize)
+                .is_some_and(|&(left, right)| (left..=right).contains(&x))
     }
+
+    /// The region without a window Batman stood on, None when it was the last one.
+    pub(crate) fn without(&self, window: (i32, i32)) -> Option<Self> {
+        let mut holes = self.holes.clone();
+        holes.push(window);
+        Region::trimmed(self.top_row, self.spans.clone(), &holes)
+    }
+
+    /// The window left, once there is only one.
+    fn only_window(&self) -> Option<(i32, i32)> {
+        let mut rows = self.rows();
+        match (rows.next(), rows.next()) {
+            (Some((row, left, right)), None) if left == right => Some((left, row)),
+            _ => None,
+        }
+    }
+
+    /// Rectangle around the windows.
+    fn bounds(&self) -> SearchArea {
+        let mut area = SearchArea::new(0, 0);
+        for (idx, (row, left, right)) in self.rows().enumerate() {
+            if idx == 0 {
+                area = SearchArea {
+                    left_col: left,
+                    right_col: right,
+                    top_row: row,
+                    bottom_row: row,
+                };
+            }
+            area.left_col = area.left_col.min(left);
+            area.right_col = area.right_col.max(right);
+            area.bottom_row = row;
+        }
+        area
+    }
+
+    fn centroid(&self) -> (f64, f64) {
+        let (mut sum_x, mut sum_y, mut windows) = (0.0, 0.0, 0.0);
+        for (row, left, right) in self.rows() {
+            let count = (right - left + 1) as f64;
+            sum_x += (left + right) as f64 / 2.0 * count;
+            sum_y += row as f64 * count;
+            windows += count;
+        }
+        for &(col, row) in &self.holes {
+            sum_x -= col as f64;
+            sum_y -= row as f64;
+            windows -= 1.0;
+        }
+        (sum_x / windows, sum_y / windows)
+    }
+
+    /// Window of the region closest to `point`.
+    fn nearest_window(&self, (x, y): (f64, f64)) -> Option<(i32, i32)> {
+        let dist = |(col, row): (i32, i32)| (col as f64 - x).powi(2) + (row as f64 - y).powi(2);
+        self.rows()
+            .map(|(row, left, right)| {
+                // the ends of a span are never holes
+                let col = (x.round() as i32).clamp(left, right);
+                let beside = (0..).flat_map(|step| [col - step, col + step]);
+                let mut cols = beside.filter(|col| (left..=right).contains(col));
+                let col = cols.find(|&col| !self.holes.contains(&(col, row)));
+                (col.unwrap_or(left), row)
+            })
+            .min_by(|a, b| dist(*a).total_cmp(&dist(*b)))
+    }
+
+    /// Same as `cut_area`, any bisector cutting each row exactly.
+    pub(crate) fn cut(
+        &self,
+        hint: Ordering,
+        previous: (i32, i32),
+        current: (i32, i32),
+    ) -> Option<Self> {
+        let ((qx, qy), (px, py)) = (previous, current);
+        let (qx, qy, px, py) = (qx as i64, qy as i64, px as i64, py as i64);
+        // dist(current)² - dist(previous)² = ax * x + ay * y + c
+        let (ax, ay) = (2 * (qx - px), 2 * (qy - py));
+        let c = px * px + py * py - qx * qx - qy * qy;
+        let spans: Vec<(i32, i32)> = self
+            .spans
+            .iter()
+            .zip(self.top_row..)
+            .map(|(&(left, right), row)| {
+                let kept =
+                    (left <= right).then(|| keep(hint, ax, ay * row as i64 + c, left, right));
+                kept.flatten().unwrap_or((1, 0))
+            })
+            .collect();
+        Region::trimmed(self.top_row, spans, &self.holes)
+    }
+}
+
+/// Coordinates worth trying on an axis to split lo..=hi from `pos`: the mirror of `pos`
+/// around the middle halves it, the others get closer when the mirror is off the building.
+fn axis_candidates(pos: i32, lo: i32, hi: i32, size: i32) -> Vec<i32> {
+    let mid = (lo + hi) / 2;
+    let mirror = lo + hi - pos;
+    let mut coords: Vec<i32> = [
+        mirror,
+        (mirror + mid) / 2,
+        mid,
+        mid + 1,
+        lo,
+        hi,
+        2 * lo - pos,
+        2 * hi - pos,
+        pos - 1,
+        pos + 1,
+    ]
+    .into_iter()
+    .map(|coord| coord.clamp(0, size - 1))
+    .filter(|&coord| coord != pos)
+    .collect();
+    coords.sort_unstable();
+    coords.dedup();
+    coords
 }
 
-impl Player {
-    fn new(pos_x: i32, pos_y: i32, jumps_left: i32, width: i32, height: i32) -> Self {
+impl Episode2Solver {
+    pub fn new(init: &Init) -> Self {
         Self {
-            pos_x,
-            pos_y,
-            jumps_left,
-            search_area: SearchArea::new(width, height),
+            pos_x: init.x0,
+            pos_y: init.y0,
+            prev: None,
+            width: init.width,
+            height: init.height,
+            jumps_left: init.jumps,
+            region: Region::new(init.width, init.height),
+        }
+    }
+
+    pub fn jumps_left(&self) -> i32 {
+        self.jumps_left
+    }
+
+    // a hint means the bomb is not on the current window
+    fn leave_window(&mut self) {
+        match self.region.without((self.pos_x, self.pos_y)) {
+            Some(region) => self.region = region,
+            None => bot_log::warn!("no window left but the current one"),
         }
     }
 
-    fn change_search_area(&mut self, bomb_dir: &str) -> &mut Self {
-        match bomb_dir {
-            "COLDER" => todo!(),
-            "WARMER" => todo!(),
-            "SAME" => todo!(),
-            "UNKNOWN" => {},
-            _ => {}
+    fn change_search_area(&mut self, hint: ThermalHint) -> &mut Self {
+        // UNKNOWN on the first turn, nothing to compare with
+        let Some(ordering) = hint.ordering() else {
+            self.leave_window();
+            return self;
+        };
+        if let Some(prev) = self.prev {
+            match self.region.cut(ordering, prev, (self.pos_x, self.pos_y)) {
+                Some(region) => self.region = region,
+                None => bot_log::warn!("{hint} leaves no window, hint ignored"),
+            }
         }
-        dbg!(self.search_area.clone());
+        self.leave_window();
+        let windows = self.region.windows();
+        bot_log::debug!("{windows} windows in {:?}", self.region.bounds());
         self
     }
 
-    /// jump to centre of jump area  
-    fn next_jump_position(&mut self, bomb_dir: &str) -> (i32, i32) {
-        self.change_search_area(bomb_dir);
-        let new_row = (self.search_area.top_row + self.search_area.bottom_row) / 2;
-        let new_col = (self.search_area.left_col + self.search_area.right_col) / 2;
-        self.jumps_left -=1;
+    /// Jumps from `pos` along one axis, on the rows or columns the area still spans.
+    fn candidates(&self, area: &SearchArea, (x, y): (i32, i32)) -> Vec<(i32, i32)> {
+        let mut jumps = Vec::new();
+        if area.left_col < area.right_col {
+            let cols = axis_candidates(x, area.left_col, area.right_col, self.width);
+            jumps.extend(cols.into_iter().map(|col| (col, y)));
+        }
+        if area.top_row < area.bottom_row {
+            let rows = axis_candidates(y, area.top_row, area.bottom_row, self.height);
+            jumps.extend(rows.into_iter().map(|row| (x, row)));
+        }
+        jumps
+    }
+
+    // windows left by the worst hint after the best jump from `pos`
+    fn best_split(&self, area: &SearchArea, pos: (i32, i32)) -> i64 {
+        if area.windows() == 1 {
+            return 0;
+        }
+        self.candidates(area, pos)
+            .into_iter()
+            .map(|jump| worst_split(area, pos, jump))
+            .min()
+            .unwrap_or(area.windows())
+    }
+
+    /// jump splitting the region best over two turns, whatever the hints: the mirror
+    /// of the current window across the centroid halves it, but can leave Batman too far
+    /// from the region for the next jump to halve it again
+    pub fn next_jump(&mut self, hint: ThermalHint) -> (i32, i32) {
+        self.change_search_area(hint);
+        let region = &self.region;
+        let pos = (self.pos_x, self.pos_y);
+        let (new_col, new_row) = match region.only_window() {
+            Some(window) => window,
+            None => {
+                let centroid = region.centroid();
+                let mirror = (
+                    (2.0 * centroid.0 - pos.0 as f64).round() as i32,
+                    (2.0 * centroid.1 - pos.1 as f64).round() as i32,
+                );
+                let mut jumps = self.candidates(&region.bounds(), pos);
+                jumps.push((mirror.0.clamp(0, self.width - 1), mirror.1.clamp(0, self.height - 1)));
+                jumps.extend(region.nearest_window(centroid));
+                jumps.retain(|&jump| jump != pos);
+
+                let cost = |jump: (i32, i32)| {
+                    let outcomes = [Ordering::Less, Ordering::Greater, Ordering::Equal]
+                        .map(|hint| region.cut(hint, pos, jump));
+                    let outcomes = outcomes.iter().flatten();
+                    let worst = outcomes.clone().map(Region::windows).max().unwrap_or(0);
+                    let next = outcomes
+                        .map(|next| self.best_split(&next.bounds(), jump).min(next.windows()))
+                        .max();
+                    let off_centroid =
+                        (jump.0 as f64 - centroid.0).abs() + (jump.1 as f64 - centroid.1).abs();
+                    (next.unwrap_or(0), worst, off_centroid)
+                };
+                jumps
+                    .into_iter()
+                    .map(|jump| (cost(jump), jump))
+                    .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+                    .map_or(pos, |(_, jump)| jump)
+            }
+        };
+        self.jumps_left -= 1;
+        self.prev = Some((self.pos_x, self.pos_y));
         self.pos_x = new_col;
         self.pos_y = new_row;
 
@@ -82,42 +387,208 @@ impl Player {
     }
 }
 
-/**
- * Auto-generated code below aims at helping you parse
- * the standard input according to the problem statement.
- **/
-fn main() {
-    let mut input_line = String::new();
-    io::stdin().read_line(&mut input_line).unwrap();
-    let inputs = input_line.split(" ").collect::<Vec<_>>();
-    let w = parse_input!(inputs[0], i32); // width of the building.
-    let h = parse_input!(inputs[1], i32); // height of the building.
-
-    let mut input_line = String::new();
-    io::stdin().read_line(&mut input_line).unwrap();
-    let n = parse_input!(input_line, i32); // maximum number of turns before game over.
-
-    let mut input_line = String::new();
-    io::stdin().read_line(&mut input_line).unwrap();
-    let inputs = input_line.split(" ").collect::<Vec<_>>();
-    let x0 = parse_input!(inputs[0], i32);
-    let y0 = parse_input!(inputs[1], i32);
-
-    let mut player = Player::new(x0, y0, n, w, h);
-    // dbg!(player.clone());
-    dbg!((x0, y0, n, w, h));
+/// Plays until the input ends, writing one jump per turn.
+pub fn run(mut input: LineReader<impl BufRead>, output: &mut impl Write) -> Result<(), ParseError> {
+    // building width and height, maximum number of turns before game over, start window
+    let init = read_init(&mut input)?;
+    let Init {
+        width: w,
+        height: h,
+        jumps: n,
+        x0,
+        y0,
+    } = init;
+
+    let mut player = Episode2Solver::new(&init);
+    bot_log::info!("building {w}x{h}, start ({x0}, {y0}), {n} jumps");
 
     // game loop
-    loop {
-        let mut input_line = String::new();
-        io::stdin().read_line(&mut input_line).unwrap();
-        let bomb_dir = input_line.trim().to_string(); // the direction of the bombs from batman's current location (U, UR, R, DR, D, DL, L or UL)
+    for turn in 0.. {
+        let hint = match read_thermal_hint(&mut input) {
+            Err(err) if err.is_end_of_input() => return Ok(()),
+            hint => hint?,
+        }; // closer or further from the bomb than the last window (WARMER, COLDER, SAME or UNKNOWN)
 
-        // dbg!(player.clone());
-        dbg!(bomb_dir.clone());
-        let (x, y) = player.next_jump_position(&bomb_dir);
+        let (x, y) = player.next_jump(hint);
+        bot_log::turn_dump!(turn, hint = hint, jump = format!("{x},{y}"));
 
         // the location of the next window Batman should jump to.
-        println!("{x} {y}");
+        write_jump(output, (x, y), input.line_number())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_transcripts {
+    use super::*;
+    use std::path::Path;
+    use transcript::{check, transcripts, Transcript};
+
+    // tests/transcripts/ep2, `UPDATE_TRANSCRIPTS=1 cargo test` accepting new jumps
+    #[test]
+    fn recorded_games() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/transcripts/ep2");
+        let mut failures = Vec::new();
+        for path in transcripts(&dir) {
+            let transcript = Transcript::load(&path);
+            let mut output = Vec::new();
+            run(LineReader::new(transcript.input().as_bytes()), &mut output).unwrap();
+            let output = String::from_utf8(output).unwrap();
+            assert_eq!(output.lines().count(), transcript.turns(), "{}", path.display());
+            if let Err(err) = check(&path, &output) {
+                failures.push(err);
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod test_search {
+    use super::*;
+
+    // jumps to find the bomb, every jump on a window of the building
+    fn play(width: i32, height: i32, start: (i32, i32), bomb: (i32, i32)) -> u32 {
+        let init = Init {
+            width,
+            height,
+            jumps: 100,
+            x0: start.0,
+            y0: start.1,
+        };
+        let mut player = Episode2Solver::new(&init);
+        let mut hint = ThermalHint::Unknown;
+        let mut pos = start;
+        for jumps in 1..100 {
+            let next = player.next_jump(hint);
+            assert!((0..width).contains(&next.0) && (0..height).contains(&next.1));
+            if next == bomb {
+                return jumps;
+            }
+            hint = ThermalHint::after_jump(pos, next, bomb);
+            pos = next;
+        }
+        panic!("bomb {bomb:?} not found from {start:?} in {width}x{height}");
+    }
+
+    fn log2_ceil(windows: i32) -> u32 {
+        32 - (windows - 1).leading_zeros()
+    }
+
+    #[test]
+    fn cuts_by_the_bisector() {
+        let area = SearchArea::new(10, 5);
+        // from column 2 to column 7: the bisector is x = 4.5
+        let warmer = cut_area(&area, Ordering::Less, (2, 3), (7, 3)).unwrap();
+        assert_eq!((warmer.left_col, warmer.right_col), (5, 9));
+        let colder = cut_area(&area, Ordering::Greater, (2, 3), (7, 3)).unwrap();
+        assert_eq!((colder.left_col, colder.right_col), (0, 4));
+        assert!(cut_area(&area, Ordering::Equal, (2, 3), (7, 3)).is_none());
+
+        // from row 0 to row 4: SAME on row 2
+        let same = cut_area(&area, Ordering::Equal, (6, 0), (6, 4)).unwrap();
+        assert_eq!((same.top_row, same.bottom_row, same.windows()), (2, 2, 10));
+        assert_eq!(worst_split(&area, (6, 0), (6, 4)), 20);
+    }
+
+    #[test]
+    fn region_counts_windows_exactly() {
+        let (width, height) = (9, 7);
+        let cuts = [
+            (Ordering::Less, (0, 0), (5, 3)),
+            (Ordering::Greater, (5, 3), (8, 1)),
+            (Ordering::Less, (8, 1), (2, 6)),
+        ];
+        let mut region = Region::new(width, height);
+        let mut windows: Vec<(i32, i32)> = (0..width)
+            .flat_map(|x| (0..height).map(move |y| (x, y)))
+            .collect();
+        let dist = |(x, y): (i32, i32), (px, py): (i32, i32)| (x - px).pow(2) + (y - py).pow(2);
+        for (hint, previous, current) in cuts {
+            region = region.cut(hint, previous, current).unwrap();
+            windows.retain(|&window| dist(window, current).cmp(&dist(window, previous)) == hint);
+            assert_eq!(region.windows(), windows.len() as i64);
+        }
+        let bounds = region.bounds();
+        assert!(windows.iter().all(|&(x, y)| {
+            (bounds.left_col..=bounds.right_col).contains(&x)
+                && (bounds.top_row..=bounds.bottom_row).contains(&y)
+        }));
+        let nearest = region.nearest_window(region.centroid()).unwrap();
+        assert!(windows.contains(&nearest));
+    }
+
+    #[test]
+    fn region_on_the_biggest_building() {
+        let region = Region::new(10000, 10000);
+        assert_eq!(region.windows(), 100_000_000);
+        // SAME between opposite corners: the anti-diagonal
+        let same = region.cut(Ordering::Equal, (0, 0), (9999, 9999)).unwrap();
+        assert_eq!(same.windows(), 10000);
+        // closer to (2, 2) than to (0, 0): x + y > 2
+        let warmer = region.cut(Ordering::Less, (0, 0), (2, 2)).unwrap();
+        assert_eq!(warmer.windows(), 100_000_000 - 6);
+        let colder = region.cut(Ordering::Greater, (0, 0), (2, 2)).unwrap();
+        assert_eq!(colder.windows(), 3);
+        let corner = colder.cut(Ordering::Less, (2, 2), (0, 0)).unwrap();
+        assert_eq!(corner.windows(), 3);
+        let corner = corner.cut(Ordering::Equal, (0, 1), (1, 0)).unwrap();
+        assert_eq!(corner.windows(), 1);
+        assert_eq!(corner.only_window(), Some((0, 0)));
+    }
+
+    #[test]
+    fn leaves_the_windows_jumped_on() {
+        let init = Init {
+            width: 5,
+            height: 1,
+            jumps: 5,
+            x0: 0,
+            y0: 0,
+        };
+        let mut player = Episode2Solver::new(&init);
+        assert_eq!(player.next_jump(ThermalHint::Unknown), (4, 0));
+        assert_eq!(player.region.windows(), 4);
+        // closer to (4, 0) than to (0, 0): (3, 0) and the current window
+        assert_eq!(player.next_jump(ThermalHint::Warmer), (3, 0));
+        assert_eq!(player.region.only_window(), Some((3, 0)));
+
+        // a hole inside a row, then the ends of the row cut off
+        let region = Region::new(5, 2).without((2, 0)).unwrap();
+        assert_eq!(region.windows(), 9);
+        let region = region.without((1, 0)).unwrap().without((0, 0)).unwrap();
+        assert_eq!(region.windows(), 7);
+        let row = region.cut(Ordering::Equal, (2, 1), (2, -1)).unwrap();
+        assert_eq!(row.nearest_window((1.0, 0.0)), Some((3, 0)));
+        assert_eq!(row.only_window(), None);
+        let last = row.without((3, 0)).unwrap();
+        assert_eq!(last.only_window(), Some((4, 0)));
+        assert_eq!(last.without((4, 0)), None);
+    }
+
+    #[test]
+    fn finds_every_bomb() {
+        for (width, height) in [(1, 10), (10, 1), (2, 2), (4, 6), (7, 3)] {
+            let windows = (0..width).flat_map(|x| (0..height).map(move |y| (x, y)));
+            let bound = log2_ceil(width * height) + 3;
+            for start in windows.clone() {
+                for bomb in windows.clone().filter(|&bomb| bomb != start) {
+                    assert!(play(width, height, start, bomb) <= bound, "{start:?} {bomb:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn big_buildings() {
+        for (width, height, start, bomb) in [
+            (10000, 10000, (0, 0), (9999, 9999)),
+            (10000, 10000, (5000, 5000), (0, 9999)),
+            (10000, 10000, (9999, 0), (4321, 1234)),
+            (8000, 8000, (3, 7998), (7999, 0)),
+            (1, 10000, (0, 0), (0, 9998)),
+        ] {
+            assert!(play(width, height, start, bomb) <= log2_ceil(width * height) + 4);
+        }
     }
 }
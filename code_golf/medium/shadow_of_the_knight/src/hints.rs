@@ -0,0 +1,246 @@
+// The hint Batman gets each turn, as written by the game:
+// - episode 1: the direction of the bomb, U, UR, R, DR, D, DL, L or UL, rows growing downwards
+// - episode 2: whether the last jump got Batman WARMER, COLDER or kept the SAME distance
+//   to the bomb, UNKNOWN on the first turn
+
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+/// Where the bomb is from Batman's window: its row compared to his, Less for up, and
+/// its column compared to his, Less for left. Never Equal twice, Batman being on the bomb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirectionHint {
+    vertical: Ordering,
+    horizontal: Ordering,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ThermalHint {
+    Colder,
+    Warmer,
+    Same,
+    Unknown,
+}
+
+/// A hint the game never sends, with the ones it does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHintError {
+    expected: &'static [&'static str],
+}
+
+impl fmt::Display for ParseHintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected one of {}", self.expected.join(", "))
+    }
+}
+
+impl std::error::Error for ParseHintError {}
+
+const DIRECTIONS: [&str; 8] = ["U", "UR", "R", "DR", "D", "DL", "L", "UL"];
+// by row then column of the bomb: before, same, after Batman's
+const DIRECTION_GRID: [[&str; 3]; 3] = [["UL", "U", "UR"], ["L", "", "R"], ["DL", "D", "DR"]];
+const THERMAL_HINTS: [&str; 4] = ["COLDER", "WARMER", "SAME", "UNKNOWN"];
+
+impl DirectionHint {
+    pub const UP: DirectionHint = DirectionHint::of(Ordering::Less, Ordering::Equal);
+    pub const UP_RIGHT: DirectionHint = DirectionHint::of(Ordering::Less, Ordering::Greater);
+    pub const RIGHT: DirectionHint = DirectionHint::of(Ordering::Equal, Ordering::Greater);
+    pub const DOWN_RIGHT: DirectionHint = DirectionHint::of(Ordering::Greater, Ordering::Greater);
+    pub const DOWN: DirectionHint = DirectionHint::of(Ordering::Greater, Ordering::Equal);
+    pub const DOWN_LEFT: DirectionHint = DirectionHint::of(Ordering::Greater, Ordering::Less);
+    pub const LEFT: DirectionHint = DirectionHint::of(Ordering::Equal, Ordering::Less);
+    pub const UP_LEFT: DirectionHint = DirectionHint::of(Ordering::Less, Ordering::Less);
+
+    pub const ALL: [DirectionHint; 8] = [
+        DirectionHint::UP,
+        DirectionHint::UP_RIGHT,
+        DirectionHint::RIGHT,
+        DirectionHint::DOWN_RIGHT,
+        DirectionHint::DOWN,
+        DirectionHint::DOWN_LEFT,
+        DirectionHint::LEFT,
+        DirectionHint::UP_LEFT,
+    ];
+
+    const fn of(vertical: Ordering, horizontal: Ordering) -> Self {
+        DirectionHint {
+            vertical,
+            horizontal,
+        }
+    }
+
+    /// None when both components are Equal: no hint is given on the bomb.
+    pub fn new(vertical: Ordering, horizontal: Ordering) -> Option<Self> {
+        let on_bomb = vertical == Ordering::Equal && horizontal == Ordering::Equal;
+        (!on_bomb).then_some(DirectionHint::of(vertical, horizontal))
+    }
+
+    /// Row of the bomb compared to Batman's row, Less when above.
+    pub fn vertical(self) -> Ordering {
+        self.vertical
+    }
+
+    /// Column of the bomb compared to Batman's column, Less when on the left.
+    pub fn horizontal(self) -> Ordering {
+        self.horizontal
+    }
+
+    pub fn as_str(self) -> &'static str {
+        // an Ordering is -1, 0 or 1
+        let index = |ordering: Ordering| (ordering as i8 + 1) as usize;
+        DIRECTION_GRID[index(self.vertical)][index(self.horizontal)]
+    }
+
+    /// Direction of the bomb from `from`, None on the bomb.
+    pub fn towards(from: (i32, i32), bomb: (i32, i32)) -> Option<Self> {
+        DirectionHint::new(bomb.1.cmp(&from.1), bomb.0.cmp(&from.0))
+    }
+}
+
+impl ThermalHint {
+    pub const ALL: [ThermalHint; 4] = [
+        ThermalHint::Colder,
+        ThermalHint::Warmer,
+        ThermalHint::Same,
+        ThermalHint::Unknown,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        THERMAL_HINTS[self as usize]
+    }
+
+    /// Hint after a jump from `previous` to `current`.
+    pub fn after_jump(previous: (i32, i32), current: (i32, i32), bomb: (i32, i32)) -> Self {
+        let dist = |(x, y): (i32, i32)| (x - bomb.0).pow(2) + (y - bomb.1).pow(2);
+        ThermalHint::from_ordering(dist(current).cmp(&dist(previous)))
+    }
+
+    /// Hint for the distance to the bomb after the jump compared to before.
+    pub fn from_ordering(ordering: Ordering) -> Self {
+        match ordering {
+            Ordering::Less => ThermalHint::Warmer,
+            Ordering::Greater => ThermalHint::Colder,
+            Ordering::Equal => ThermalHint::Same,
+        }
+    }
+
+    /// Distance to the bomb after the jump compared to before, None on the first turn.
+    pub fn ordering(self) -> Option<Ordering> {
+        match self {
+            ThermalHint::Warmer => Some(Ordering::Less),
+            ThermalHint::Colder => Some(Ordering::Greater),
+            ThermalHint::Same => Some(Ordering::Equal),
+            ThermalHint::Unknown => None,
+        }
+    }
+}
+
+impl fmt::Display for DirectionHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ThermalHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DirectionHint {
+    type Err = ParseHintError;
+
+    // the vertical letter first, one letter at least
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (vertical, rest) = match s.chars().next() {
+            Some('U') => (Ordering::Less, &s[1..]),
+            Some('D') => (Ordering::Greater, &s[1..]),
+            _ => (Ordering::Equal, s),
+        };
+        let horizontal = match rest {
+            "L" => Some(Ordering::Less),
+            "R" => Some(Ordering::Greater),
+            "" => Some(Ordering::Equal),
+            _ => None,
+        };
+        horizontal
+            .and_then(|horizontal| DirectionHint::new(vertical, horizontal))
+            .ok_or(ParseHintError {
+                expected: &DIRECTIONS,
+            })
+    }
+}
+
+impl FromStr for ThermalHint {
+    type Err = ParseHintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ThermalHint::ALL
+            .into_iter()
+            .find(|hint| hint.as_str() == s.trim())
+            .ok_or(ParseHintError {
+                expected: &THERMAL_HINTS,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test_hints {
+    use super::*;
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        for hint in DirectionHint::ALL {
+            assert_eq!(hint.to_string().parse::<DirectionHint>(), Ok(hint));
+        }
+        for hint in ThermalHint::ALL {
+            assert_eq!(hint.to_string().parse::<ThermalHint>(), Ok(hint));
+        }
+        assert_eq!("UR\n".parse::<DirectionHint>(), Ok(DirectionHint::UP_RIGHT));
+        for invalid in ["", "RU", "UD", "LR", "URR", "u", "X"] {
+            assert!(invalid.parse::<DirectionHint>().is_err(), "{invalid:?}");
+        }
+        let err = "RU".parse::<DirectionHint>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected one of U, UR, R, DR, D, DL, L, UL"
+        );
+        assert!("WARM".parse::<ThermalHint>().is_err());
+    }
+
+    #[test]
+    fn hints_from_the_bomb() {
+        let bombs = [
+            (5, 2),
+            (7, 2),
+            (7, 5),
+            (7, 8),
+            (5, 8),
+            (3, 8),
+            (3, 5),
+            (3, 2),
+        ];
+        let hints = bombs.map(|bomb| DirectionHint::towards((5, 5), bomb).unwrap());
+        assert_eq!(hints, DirectionHint::ALL);
+        assert_eq!(DirectionHint::towards((5, 5), (5, 5)), None);
+        assert_eq!(DirectionHint::new(Ordering::Equal, Ordering::Equal), None);
+        let down_left = DirectionHint::DOWN_LEFT;
+        assert_eq!(down_left.vertical(), Ordering::Greater);
+        assert_eq!(down_left.horizontal(), Ordering::Less);
+
+        let bomb = (3, 3);
+        assert_eq!(
+            ThermalHint::after_jump((0, 0), (4, 0), bomb),
+            ThermalHint::Warmer
+        );
+        assert_eq!(
+            ThermalHint::after_jump((4, 0), (2, 0), bomb),
+            ThermalHint::Same
+        );
+        assert_eq!(
+            ThermalHint::after_jump((2, 0), (9, 9), bomb),
+            ThermalHint::Colder
+        );
+        assert_eq!(ThermalHint::Unknown.ordering(), None);
+    }
+}
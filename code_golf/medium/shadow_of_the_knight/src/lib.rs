@@ -1,2 +1,13 @@
-mod ep1;
-mod ep2;
\ No newline at end of file
+// Shadow of the Knight, both episodes: Batman jumps between the windows of a building
+// to find a bomb, from a hint on each turn
+pub mod ep1;
+pub mod ep2;
+pub mod hints;
+pub mod protocol;
+pub mod referee;
+pub mod search_area;
+
+pub use ep1::Episode1Solver;
+pub use ep2::Episode2Solver;
+pub use hints::{DirectionHint, ThermalHint};
+pub use search_area::SearchArea;
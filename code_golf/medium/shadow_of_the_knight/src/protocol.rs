@@ -0,0 +1,133 @@
+// Shadow of the Knight input protocol:
+// - init: building width and height, the number of jumps, Batman's starting window
+// - each turn: one hint, a direction (U, UR, ...) in episode 1, a temperature in episode 2
+
+use crate::hints::{DirectionHint, ThermalHint};
+use line_reader::{ErrorKind, LineReader, ParseError};
+use std::io::{BufRead, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Init {
+    pub width: i32,
+    pub height: i32,
+    pub jumps: i32,
+    pub x0: i32,
+    pub y0: i32,
+}
+
+pub fn read_init(reader: &mut LineReader<impl BufRead>) -> Result<Init, ParseError> {
+    let line = reader.next_line("building")?;
+    let mut fields = line.fields();
+    let width = positive(fields.next("width")?, line.number(), "width")?;
+    let height = positive(fields.next("height")?, line.number(), "height")?;
+    fields.end()?;
+
+    let jumps = reader.next_value("number of jumps")?;
+
+    let line = reader.next_line("start window")?;
+    let mut fields = line.fields();
+    let x0 = fields.next("x0")?;
+    let y0 = fields.next("y0")?;
+    fields.end()?;
+    if !(0..width).contains(&x0) || !(0..height).contains(&y0) {
+        let kind = ErrorKind::Invalid {
+            value: format!("{x0} {y0}"),
+            reason: format!("outside the {width}x{height} building"),
+        };
+        return Err(line.error("start window", kind));
+    }
+    Ok(Init {
+        width,
+        height,
+        jumps,
+        x0,
+        y0,
+    })
+}
+
+/// Reads the episode 1 hint: the direction of the bomb.
+pub fn read_direction(reader: &mut LineReader<impl BufRead>) -> Result<DirectionHint, ParseError> {
+    reader.next_value("bomb direction")
+}
+
+/// Reads the episode 2 hint: closer, further or as far from the bomb as the last window.
+pub fn read_thermal_hint(reader: &mut LineReader<impl BufRead>) -> Result<ThermalHint, ParseError> {
+    reader.next_value("bomb distance")
+}
+
+/// Writes the window Batman jumps to, answering the hint on `line`.
+pub fn write_jump(
+    output: &mut impl Write,
+    (x, y): (i32, i32),
+    line: usize,
+) -> Result<(), ParseError> {
+    writeln!(output, "{x} {y}")
+        .map_err(|err| ParseError::new(line, "jump", ErrorKind::Io(err.to_string())))
+}
+
+fn positive(value: i32, line: usize, name: &str) -> Result<i32, ParseError> {
+    if value > 0 {
+        return Ok(value);
+    }
+    let kind = ErrorKind::Invalid {
+        value: value.to_string(),
+        reason: "expected a positive size".to_string(),
+    };
+    Err(ParseError::new(line, name, kind))
+}
+
+#[cfg(test)]
+mod test_protocol {
+    use super::*;
+
+    fn reader(input: &str) -> LineReader<&[u8]> {
+        LineReader::new(input.as_bytes())
+    }
+
+    #[test]
+    fn reads_init_and_hints() {
+        let mut input = reader("10 10 \n6\n2 5\nUR\nWARMER\n");
+        assert_eq!(
+            read_init(&mut input),
+            Ok(Init {
+                width: 10,
+                height: 10,
+                jumps: 6,
+                x0: 2,
+                y0: 5
+            })
+        );
+        assert_eq!(read_direction(&mut input), Ok(DirectionHint::UP_RIGHT));
+        assert_eq!(read_thermal_hint(&mut input), Ok(ThermalHint::Warmer));
+        assert!(read_direction(&mut input).unwrap_err().is_end_of_input());
+    }
+
+    #[test]
+    fn writes_jumps_until_the_output_closes() {
+        let mut output = Vec::new();
+        assert_eq!(write_jump(&mut output, (3, 4), 5), Ok(()));
+        assert_eq!(output, b"3 4\n");
+
+        let mut closed: &mut [u8] = &mut [];
+        let err = write_jump(&mut closed, (3, 4), 5).unwrap_err();
+        assert_eq!((err.line, err.field.as_str()), (5, "jump"));
+        assert!(matches!(err.kind, ErrorKind::Io(_)));
+    }
+
+    #[test]
+    fn reports_broken_lines() {
+        let err = read_init(&mut reader("10\n")).unwrap_err();
+        assert_eq!(err.to_string(), "line 1, height: missing");
+        let err = read_init(&mut reader("10 0\n")).unwrap_err();
+        assert_eq!(err.field, "height");
+        let err = read_init(&mut reader("4 4\n6\n2 4\n")).unwrap_err();
+        assert_eq!((err.line, err.field.as_str()), (3, "start window"));
+
+        let err = read_direction(&mut reader("UP\n")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, bomb direction: invalid \"UP\": expected one of U, UR, R, DR, D, DL, L, UL"
+        );
+        assert!(read_thermal_hint(&mut reader("WARM\n")).is_err());
+    }
+}
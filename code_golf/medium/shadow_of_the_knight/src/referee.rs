@@ -0,0 +1,304 @@
+// Local referee for Shadow of the Knight, in place of the CodinGame one:
+// - the bomb is on a window of the building, other than Batman's start
+// - each turn Batman gets a hint, then jumps: the direction of the bomb from his window in
+//   episode 1, in episode 2 whether he got WARMER, COLDER or kept the SAME distance to the bomb
+//   with his last jump (UNKNOWN on the first turn)
+// - the game is won when a jump lands on the bomb, lost after N jumps or a jump out of the building
+// - the adversary places no bomb up front: each turn it gives the hint keeping the most windows
+//   consistent with every hint so far, and gives in when Batman jumps on the last one
+
+use crate::{
+    ep2::Region,
+    hints::{DirectionHint, ThermalHint},
+    protocol::Init,
+    search_area::SearchArea,
+    Episode1Solver, Episode2Solver,
+};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Episode {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bomb {
+    At((i32, i32)),
+    Adversary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    pub hint: String,
+    pub jump: (i32, i32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub turns: Vec<Turn>,
+    pub found: bool,
+}
+
+/// Worst case of the games against every bomb of the building, Batman's start left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sweep {
+    pub games: usize,
+    pub worst_jumps: usize,
+    pub worst_bomb: (i32, i32),
+    // bombs not found within N jumps
+    pub missed: Vec<(i32, i32)>,
+}
+
+pub fn inside(init: &Init, (x, y): (i32, i32)) -> bool {
+    (0..init.width).contains(&x) && (0..init.height).contains(&y)
+}
+
+// Gives the hints of one game
+enum Hints {
+    Directions((i32, i32)),
+    Distances((i32, i32)),
+    // windows left
+    DirectionAdversary(SearchArea),
+    // windows left, the ones Batman jumped on taken out
+    DistanceAdversary(Region),
+}
+
+impl Hints {
+    fn new(episode: Episode, bomb: Bomb, init: &Init) -> Self {
+        match (episode, bomb) {
+            (Episode::One, Bomb::At(bomb)) => Hints::Directions(bomb),
+            (Episode::Two, Bomb::At(bomb)) => Hints::Distances(bomb),
+            (Episode::One, Bomb::Adversary) => {
+                Hints::DirectionAdversary(SearchArea::new(init.width, init.height))
+            }
+            (Episode::Two, Bomb::Adversary) => {
+                Hints::DistanceAdversary(Region::new(init.width, init.height))
+            }
+        }
+    }
+
+    /// Hint for Batman on `current`, coming from `previous`; None when he is on the bomb.
+    fn hint(&mut self, previous: Option<(i32, i32)>, current: (i32, i32)) -> Option<String> {
+        match self {
+            Hints::Directions(bomb) | Hints::Distances(bomb) if current == *bomb => None,
+            Hints::Directions(bomb) => {
+                DirectionHint::towards(current, *bomb).map(|hint| hint.to_string())
+            }
+            Hints::Distances(bomb) => {
+                let hint = match previous {
+                    Some(previous) => ThermalHint::after_jump(previous, current, *bomb),
+                    None => ThermalHint::Unknown,
+                };
+                Some(hint.to_string())
+            }
+            Hints::DirectionAdversary(area) => {
+                let outcomes = DirectionHint::ALL.map(|hint| {
+                    let mut kept = area.clone();
+                    kept.cut_rows(hint.vertical(), current.1);
+                    kept.cut_columns(hint.horizontal(), current.0);
+                    (kept.windows(), kept, hint)
+                });
+                // the first of the largest, in the order of DirectionHint::ALL
+                let (windows, kept, hint) = outcomes
+                    .into_iter()
+                    .rev()
+                    .max_by_key(|(windows, ..)| *windows)?;
+                if windows == 0 {
+                    return None;
+                }
+                *area = kept;
+                Some(hint.to_string())
+            }
+            Hints::DistanceAdversary(region) => {
+                // Batman on the last window left
+                *region = region.without(current)?;
+                let Some(previous) = previous else {
+                    return Some(ThermalHint::Unknown.to_string());
+                };
+                let outcomes = [Ordering::Less, Ordering::Greater, Ordering::Equal]
+                    .into_iter()
+                    .filter_map(|ordering| {
+                        let kept = region.cut(ordering, previous, current)?;
+                        Some((kept.windows(), kept, ordering))
+                    });
+                // the first of the largest: WARMER, COLDER, then SAME
+                let (windows, kept, ordering) =
+                    outcomes.rev().max_by_key(|(windows, ..)| *windows)?;
+                if windows == 0 {
+                    return None;
+                }
+                *region = kept;
+                Some(ThermalHint::from_ordering(ordering).to_string())
+            }
+        }
+    }
+}
+
+enum Batman {
+    One(Episode1Solver),
+    Two(Episode2Solver),
+}
+
+impl Batman {
+    // the hint as the bot reads it from the game
+    fn jump(&mut self, hint: &str) -> (i32, i32) {
+        match self {
+            Batman::One(player) => player.next_jump(hint.parse().expect("direction hint")),
+            Batman::Two(player) => player.next_jump(hint.parse().expect("thermal hint")),
+        }
+    }
+}
+
+/// Plays the solver of the episode against a bomb on another window of the building.
+pub fn play(episode: Episode, init: &Init, bomb: Bomb) -> Game {
+    let start = (init.x0, init.y0);
+    if let Bomb::At(bomb) = bomb {
+        assert!(inside(init, bomb), "bomb {bomb:?} outside the building");
+        assert_ne!(bomb, start, "bomb on the start window");
+    }
+    let mut batman = match episode {
+        Episode::One => Batman::One(Episode1Solver::new(init)),
+        Episode::Two => Batman::Two(Episode2Solver::new(init)),
+    };
+    let mut hints = Hints::new(episode, bomb, init);
+    let mut hint = hints
+        .hint(None, start)
+        .expect("no window left for the bomb");
+    let mut position = start;
+    let mut turns = Vec::new();
+    while turns.len() < init.jumps.max(0) as usize {
+        let next = batman.jump(&hint);
+        turns.push(Turn { hint, jump: next });
+        if !inside(init, next) {
+            break;
+        }
+        match hints.hint(Some(position), next) {
+            Some(next_hint) => hint = next_hint,
+            None => return Game { turns, found: true },
+        }
+        position = next;
+    }
+    Game {
+        turns,
+        found: false,
+    }
+}
+
+pub fn sweep(episode: Episode, init: &Init) -> Sweep {
+    let mut sweep = Sweep {
+        games: 0,
+        worst_jumps: 0,
+        worst_bomb: (init.x0, init.y0),
+        missed: Vec::new(),
+    };
+    let bombs = (0..init.height).flat_map(|y| (0..init.width).map(move |x| (x, y)));
+    for bomb in bombs.filter(|&bomb| bomb != (init.x0, init.y0)) {
+        let game = play(episode, init, Bomb::At(bomb));
+        sweep.games += 1;
+        if !game.found {
+            sweep.missed.push(bomb);
+        } else if game.turns.len() > sweep.worst_jumps {
+            sweep.worst_jumps = game.turns.len();
+            sweep.worst_bomb = bomb;
+        }
+    }
+    sweep
+}
+
+#[cfg(test)]
+mod test_referee {
+    use super::*;
+
+    fn init(width: i32, height: i32, jumps: i32, x0: i32, y0: i32) -> Init {
+        Init {
+            width,
+            height,
+            jumps,
+            x0,
+            y0,
+        }
+    }
+
+    // binary search on the longest side, one more jump to land on the bomb
+    fn jumps_needed(width: i32, height: i32) -> usize {
+        (32 - width.max(height).leading_zeros()) as usize
+    }
+
+    #[test]
+    fn finds_the_bomb() {
+        let game = play(Episode::One, &init(10, 10, 6, 2, 5), Bomb::At((7, 4)));
+        assert!(game.found);
+        assert_eq!(game.turns[0].hint, "UR");
+        assert_eq!(game.turns.last().unwrap().jump, (7, 4));
+
+        let game = play(Episode::One, &init(10, 10, 1, 2, 5), Bomb::At((7, 4)));
+        assert!(!game.found);
+        assert_eq!(game.turns.len(), 1);
+
+        let game = play(Episode::Two, &init(5, 16, 80, 1, 1), Bomb::At((3, 12)));
+        assert!(game.found);
+        assert_eq!(game.turns[0].hint, "UNKNOWN");
+    }
+
+    #[test]
+    fn small_buildings_within_log2_jumps() {
+        for (width, height) in [(1, 2), (1, 36), (4, 8), (10, 10), (13, 7), (40, 60)] {
+            let needed = jumps_needed(width, height);
+            for (x0, y0) in [(0, 0), (width - 1, height - 1), (width / 2, height / 3)] {
+                let sweep = sweep(Episode::One, &init(width, height, needed as i32, x0, y0));
+                assert_eq!(sweep.games, (width * height - 1) as usize);
+                assert!(
+                    sweep.missed.is_empty(),
+                    "{width}x{height}: {:?}",
+                    sweep.missed
+                );
+                assert!(sweep.worst_jumps <= needed);
+            }
+        }
+    }
+
+    #[test]
+    fn adversary_keeps_the_bomb_consistent() {
+        // the last jump is on a window matching every hint given
+        for episode in [Episode::One, Episode::Two] {
+            let init = init(9, 7, 40, 2, 5);
+            let game = play(episode, &init, Bomb::Adversary);
+            assert!(game.found);
+            let bomb = game.turns.last().unwrap().jump;
+            let replay = play(episode, &init, Bomb::At(bomb));
+            assert_eq!(replay, game);
+        }
+    }
+
+    #[test]
+    fn adversary_within_the_jumps_given() {
+        // episode 1: a binary search on the longest side
+        for (width, height, x0, y0) in [
+            (10, 10, 2, 5),
+            (4, 8, 2, 3),
+            (1, 36, 0, 7),
+            (40, 60, 6, 6),
+            (10000, 10000, 6765, 9222),
+        ] {
+            let jumps = jumps_needed(width, height);
+            let init = init(width, height, jumps as i32, x0, y0);
+            assert!(
+                play(Episode::One, &init, Bomb::Adversary).found,
+                "{width}x{height}"
+            );
+        }
+        // episode 2: a few jumps more than log2 of the windows, 31 in the biggest building
+        for (width, height, jumps, x0, y0) in [
+            (5, 16, 10, 1, 1),
+            (18, 32, 13, 17, 31),
+            (1, 10000, 18, 0, 0),
+            (1000, 1000, 24, 0, 0),
+            (10000, 10000, 31, 9999, 0),
+        ] {
+            let init = init(width, height, jumps, x0, y0);
+            let game = play(Episode::Two, &init, Bomb::Adversary);
+            assert!(game.found, "{width}x{height} from ({x0}, {y0})");
+        }
+    }
+}
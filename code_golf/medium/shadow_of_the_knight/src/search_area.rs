@@ -0,0 +1,95 @@
+// Rectangle of windows the bomb can be in, shared by both episodes:
+// episode 1 cuts it by the direction hints, episode 2 estimates its region with it.
+
+use std::cmp::Ordering;
+
+/// Columns and rows of the windows, bounds included.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct SearchArea {
+    pub left_col: i32,
+    pub right_col: i32,
+    pub top_row: i32,
+    pub bottom_row: i32,
+}
+
+impl SearchArea {
+    /// The whole building.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            left_col: 0,
+            right_col: width - 1,
+            top_row: 0,
+            bottom_row: height - 1,
+        }
+    }
+
+    pub fn set_rows(&mut self, top: i32, bottom: i32) {
+        self.top_row = top;
+        self.bottom_row = bottom;
+    }
+
+    pub fn set_columns(&mut self, left: i32, right: i32) {
+        self.left_col = left;
+        self.right_col = right;
+    }
+
+    /// Keeps the rows on the `side` of `row`: above it for Less, below for Greater,
+    /// the row itself for Equal.
+    pub fn cut_rows(&mut self, side: Ordering, row: i32) {
+        let (top, bottom) = side_of(side, row, self.top_row, self.bottom_row);
+        self.set_rows(top, bottom);
+    }
+
+    /// Keeps the columns on the `side` of `col`, Less for the left.
+    pub fn cut_columns(&mut self, side: Ordering, col: i32) {
+        let (left, right) = side_of(side, col, self.left_col, self.right_col);
+        self.set_columns(left, right);
+    }
+
+    pub fn windows(&self) -> i64 {
+        let cols = (self.right_col - self.left_col + 1).max(0) as i64;
+        let rows = (self.bottom_row - self.top_row + 1).max(0) as i64;
+        cols * rows
+    }
+
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        (self.left_col..=self.right_col).contains(&x)
+            && (self.top_row..=self.bottom_row).contains(&y)
+    }
+}
+
+// part of lo..=hi before `at`, after it, or `at` itself
+fn side_of(side: Ordering, at: i32, lo: i32, hi: i32) -> (i32, i32) {
+    match side {
+        Ordering::Less => (lo, hi.min(at - 1)),
+        Ordering::Greater => (lo.max(at + 1), hi),
+        Ordering::Equal => (lo.max(at), hi.min(at)),
+    }
+}
+
+#[cfg(test)]
+mod test_search_area {
+    use super::*;
+
+    #[test]
+    fn cuts_rows_and_columns_through_a_window() {
+        let area = SearchArea::new(10, 8);
+        let cut = |vertical, horizontal| {
+            let mut area = area.clone();
+            area.cut_rows(vertical, 5);
+            area.cut_columns(horizontal, 2);
+            (area.left_col, area.right_col, area.top_row, area.bottom_row)
+        };
+        // up right: above row 5, right of column 2
+        assert_eq!(cut(Ordering::Less, Ordering::Greater), (3, 9, 0, 4));
+        // left: row 5, left of column 2
+        assert_eq!(cut(Ordering::Equal, Ordering::Less), (0, 1, 5, 5));
+        // down: below row 5, column 2
+        assert_eq!(cut(Ordering::Greater, Ordering::Equal), (2, 2, 6, 7));
+
+        let mut corner = SearchArea::new(10, 8);
+        corner.cut_columns(Ordering::Less, 0);
+        assert_eq!(corner.windows(), 0);
+        assert!(area.contains((9, 7)) && !area.contains((10, 0)));
+    }
+}
@@ -0,0 +1,112 @@
+use std::{fmt, str::FromStr};
+
+// the four inputs shared by every mini-game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    Up,
+    Left,
+    Down,
+    Right,
+}
+
+// spaces moved in the hurdle race, and whether the first space is jumped over
+const HURDLE_STRIDES: [(i32, bool); 4] = [(2, true), (1, false), (2, false), (3, false)];
+
+// unit direction pushing the archery cursor
+const ARCHERY_DIRECTIONS: [(i32, i32); 4] = [(0, -1), (-1, 0), (0, 1), (1, 0)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseActionError(String);
+
+impl fmt::Display for ParseActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid action {:?}", self.0)
+    }
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [Action::Up, Action::Left, Action::Down, Action::Right];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Up => "UP",
+            Action::Left => "LEFT",
+            Action::Down => "DOWN",
+            Action::Right => "RIGHT",
+        }
+    }
+
+    /// Letter used by the roller and diving GPU.
+    pub fn initial(self) -> char {
+        match self {
+            Action::Up => 'U',
+            Action::Left => 'L',
+            Action::Down => 'D',
+            Action::Right => 'R',
+        }
+    }
+
+    pub fn from_initial(initial: char) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.initial() == initial)
+    }
+
+    pub fn hurdle_stride(self) -> (i32, bool) {
+        HURDLE_STRIDES[self.index()]
+    }
+
+    /// Spaces moved in the hurdle race when nothing is in the way.
+    pub fn hurdle_spaces(self) -> i32 {
+        self.hurdle_stride().0
+    }
+
+    pub fn archery_direction(self) -> (i32, i32) {
+        ARCHERY_DIRECTIONS[self.index()]
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|a| a.as_str() == s.trim())
+            .ok_or_else(|| ParseActionError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test_action {
+    use super::*;
+
+    #[test]
+    fn display_and_parse_round_trip() {
+        for action in Action::ALL {
+            assert_eq!(action.to_string().parse::<Action>(), Ok(action));
+            assert_eq!(Action::from_initial(action.initial()), Some(action));
+        }
+        assert_eq!("RIGHT\n".parse::<Action>(), Ok(Action::Right));
+        assert!("NOTHING".parse::<Action>().is_err());
+        assert_eq!(Action::from_initial('X'), None);
+    }
+
+    #[test]
+    fn effect_tables() {
+        assert_eq!(Action::Up.hurdle_stride(), (2, true));
+        assert_eq!(Action::Down.hurdle_stride(), (2, false));
+        assert_eq!(Action::Left.hurdle_spaces(), 1);
+        assert_eq!(Action::Right.hurdle_spaces(), 3);
+        assert_eq!(Action::Up.archery_direction(), (0, -1));
+        assert_eq!(Action::Right.archery_direction(), (1, 0));
+    }
+}
@@ -0,0 +1,174 @@
+// Archery simulator, following the referee rules:
+// - the GPU holds the wind strength of each remaining turn
+// - every player moves its cursor by the current wind in the chosen direction
+// - cursors stay within [-20, 20] on both axes
+// - once the wind runs out, the cursor closest to (0, 0) wins
+
+use crate::{
+    action::Action,
+    mini_game::{rank_by, MiniGame},
+};
+
+pub const MAX_COORD: i32 = 20;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Archery {
+    wind: Vec<i32>,
+    cursors: [(i32, i32); 3],
+}
+
+impl Archery {
+    pub fn new(wind: &str, cursors: [(i32, i32); 3]) -> Self {
+        Archery {
+            wind: wind
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .map(|strength| strength as i32)
+                .collect(),
+            cursors,
+        }
+    }
+
+    /// Archery GPU is the sequence of wind strengths.
+    pub fn matches(gpu: &str) -> bool {
+        !gpu.is_empty() && gpu.chars().all(|c| c.is_ascii_digit())
+    }
+
+    pub fn cursor(&self, player: usize) -> (i32, i32) {
+        self.cursors[player]
+    }
+
+    /// Wind strength for the coming turn, None once the game is over.
+    pub fn wind(&self) -> Option<i32> {
+        self.wind.first().copied()
+    }
+
+    pub fn turns_left(&self) -> usize {
+        self.wind.len()
+    }
+
+    /// Cursor pushed by `wind` in the direction of `action`.
+    pub fn wind_step(cursor: (i32, i32), wind: i32, action: Action) -> (i32, i32) {
+        let (dx, dy) = action.archery_direction();
+        (
+            (cursor.0 + dx * wind).clamp(-MAX_COORD, MAX_COORD),
+            (cursor.1 + dy * wind).clamp(-MAX_COORD, MAX_COORD),
+        )
+    }
+
+    /// My squared distance to the target once I play this action.
+    pub fn distance_after(&self, player: usize, action: Action) -> i32 {
+        let wind = self.wind().unwrap_or(0);
+        Self::squared_distance(Self::wind_step(self.cursors[player], wind, action))
+    }
+
+    pub fn distance(&self, player: usize) -> i32 {
+        Self::squared_distance(self.cursors[player])
+    }
+
+    fn squared_distance((x, y): (i32, i32)) -> i32 {
+        x * x + y * y
+    }
+}
+
+impl MiniGame for Archery {
+    /// reg_0..reg_5 are the (x, y) cursor of each player, reg_6 is unused.
+    fn parse(gpu: &str, regs: [i32; 7]) -> Option<Self> {
+        let cursors = [(regs[0], regs[1]), (regs[2], regs[3]), (regs[4], regs[5])];
+        Self::matches(gpu).then(|| Archery::new(gpu, cursors))
+    }
+
+    /// Uses up one wind.
+    fn play(&self, actions: [Action; 3]) -> Self {
+        let mut next = self.clone();
+        if let Some(wind) = self.wind() {
+            for (cursor, action) in next.cursors.iter_mut().zip(actions) {
+                *cursor = Self::wind_step(*cursor, wind, action);
+            }
+            next.wind.remove(0);
+        }
+        next
+    }
+
+    fn after_move(&self, player: usize, action: Action) -> Self {
+        let mut next = self.clone();
+        if let Some(wind) = self.wind() {
+            next.cursors[player] = Self::wind_step(self.cursors[player], wind, action);
+            next.wind.remove(0);
+        }
+        next
+    }
+
+    fn is_over(&self) -> bool {
+        self.wind.is_empty()
+    }
+
+    /// Closest to the target places best.
+    fn ranking(&self) -> [usize; 3] {
+        rank_by([0, 1, 2].map(|player| -self.distance(player)))
+    }
+
+    fn progress(&self, player: usize) -> i32 {
+        -self.distance(player)
+    }
+
+    fn gpu(&self) -> String {
+        self.wind
+            .iter()
+            .map(|strength| strength.to_string())
+            .collect()
+    }
+
+    fn registers(&self) -> [i32; 7] {
+        let [(x0, y0), (x1, y1), (x2, y2)] = self.cursors;
+        [x0, y0, x1, y1, x2, y2, -1]
+    }
+}
+
+#[cfg(test)]
+mod test_archery {
+    use super::*;
+    use crate::action::Action::*;
+    const REGS: [i32; 7] = [3, -4, 0, 0, -20, 20, -1];
+
+    #[test]
+    fn parse_registers() {
+        let archery = Archery::parse("9914", REGS).unwrap();
+
+        assert_eq!(archery.cursor(0), (3, -4));
+        assert_eq!(archery.cursor(2), (-20, 20));
+        assert_eq!(archery.wind(), Some(9));
+        assert_eq!(archery.turns_left(), 4);
+        assert!(Archery::matches("9914"));
+        assert!(!Archery::matches(".....#...#"));
+    }
+
+    #[test]
+    fn wind_moves_and_clamps_cursor() {
+        assert_eq!(Archery::wind_step((3, -4), 5, Up), (3, -9));
+        assert_eq!(Archery::wind_step((3, -4), 5, Down), (3, 1));
+        assert_eq!(Archery::wind_step((3, -4), 5, Left), (-2, -4));
+        assert_eq!(Archery::wind_step((18, -4), 5, Right), (20, -4));
+    }
+
+    #[test]
+    fn simulate_uses_up_wind() {
+        let archery = Archery::parse("91", REGS).unwrap();
+        let next = archery.play([Down, Right, Right]);
+
+        assert_eq!(next.cursor(0), (3, 5));
+        assert_eq!(next.cursor(1), (9, 0));
+        assert_eq!(next.cursor(2), (-11, 20));
+        assert_eq!(next.wind(), Some(1));
+
+        let done = next.play([Up, Left, Right]);
+        assert!(done.is_over());
+        assert_eq!(done.play([Up, Up, Up]), done);
+    }
+
+    #[test]
+    fn ranking_by_squared_distance() {
+        let archery = Archery::new("", [(3, 4), (0, 5), (1, 1)]);
+        assert_eq!(archery.ranking(), [1, 1, 0]);
+    }
+}
@@ -0,0 +1,192 @@
+// Plays a match between three bots on this machine.
+// Each bot is a shell command, talking the game protocol over its stdin and stdout:
+//     cargo run --bin referee -- target/release/olymbits target/release/olymbits ./other_bot
+// The games come from a random seed, printed with the scores; `--seed <seed>` replays them.
+// The bots' stderr is passed through, except their turn dumps, printed after the scores
+// with `--dumps`. `--record <file>` stores the input of player 0 as a transcript for golden tests.
+
+use bot_log::TurnDump;
+use olymbits::{action::Action, generator::Generator, referee::Referee};
+use rand::Rng;
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Write},
+    process::{self, Child, ChildStdin, ChildStdout, Command, Stdio},
+    thread::{self, JoinHandle},
+};
+use transcript::Transcript;
+
+struct Bot {
+    command: String,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    // collects the turn dumps until the bot exits
+    stderr: Option<JoinHandle<Vec<TurnDump>>>,
+}
+
+impl Bot {
+    fn spawn(player: usize, command: &str) -> Result<Self, String> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            // exec, for kill to reach the bot rather than the shell
+            .arg(format!("exec {command}"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("cannot start `{command}`: {err}"))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let stderr = BufReader::new(child.stderr.take().unwrap());
+        let stderr = thread::spawn(move || {
+            let mut dumps = Vec::new();
+            for line in stderr.lines().map_while(Result::ok) {
+                match TurnDump::parse(&line) {
+                    Some(dump) => dumps.push(dump),
+                    None => eprintln!("[player {player}] {line}"),
+                }
+            }
+            dumps
+        });
+        Ok(Bot {
+            command: command.to_string(),
+            child,
+            stdin,
+            stdout,
+            stderr: Some(stderr),
+        })
+    }
+
+    fn send(&mut self, lines: &[String]) -> Result<(), String> {
+        lines
+            .iter()
+            .try_for_each(|line| writeln!(self.stdin, "{line}"))
+            .and_then(|_| self.stdin.flush())
+            .map_err(|err| format!("`{}` stopped reading: {err}", self.command))
+    }
+
+    fn read_action(&mut self) -> Result<Action, String> {
+        let mut line = String::new();
+        match self.stdout.read_line(&mut line) {
+            Ok(0) => Err(format!("`{}` exited", self.command)),
+            Ok(_) => line
+                .parse()
+                .map_err(|err| format!("`{}` played an invalid action: {err}", self.command)),
+            Err(err) => Err(format!("cannot read `{}`: {err}", self.command)),
+        }
+    }
+
+    /// Stops the bot and returns its turn dumps.
+    fn finish(mut self) -> Vec<TurnDump> {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        self.stderr
+            .take()
+            .and_then(|stderr| stderr.join().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+struct Options {
+    seed: Option<u64>,
+    dumps: bool,
+    record: Option<String>,
+    commands: Vec<String>,
+}
+
+fn play_match(options: &Options, seed: u64) -> Result<(), String> {
+    let commands = &options.commands;
+    let mut bots = commands
+        .iter()
+        .enumerate()
+        .map(|(player, command)| Bot::spawn(player, command))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut referee = Referee::new(Generator::new(seed));
+    let bot_args: Vec<&str> = commands[0].split_whitespace().skip(1).collect();
+    let mut transcript = Transcript::new(&bot_args);
+    transcript.comment(&format!("seed {seed}, player 0 `{}`", commands[0]));
+    transcript.push_block(&referee.init_lines(0));
+
+    for (player, bot) in bots.iter_mut().enumerate() {
+        bot.send(&referee.init_lines(player))?;
+    }
+    while !referee.is_over() {
+        let lines = referee.turn_lines();
+        transcript.push_block(&lines);
+        let mut actions = [Action::Up; 3];
+        for (action, bot) in actions.iter_mut().zip(bots.iter_mut()) {
+            bot.send(&lines)?;
+            *action = bot.read_action()?;
+        }
+        referee.play_turn(actions);
+    }
+
+    if let Some(path) = &options.record {
+        fs::write(path, transcript.to_string())
+            .map_err(|err| format!("cannot write {path}: {err}"))?;
+    }
+
+    println!("seed {}", referee.seed());
+    for (player, (score, command)) in referee.scores().iter().zip(commands).enumerate() {
+        println!("player {player} `{command}`: {score}");
+    }
+
+    for (player, bot) in bots.into_iter().enumerate() {
+        let dumps = bot.finish();
+        if options.dumps {
+            dumps
+                .iter()
+                .for_each(|dump| println!("player {player} {dump}"));
+        }
+    }
+    Ok(())
+}
+
+// the value of an option, removed from the arguments
+fn take_option(args: &mut Vec<String>, name: &str) -> Option<Option<String>> {
+    let Some(idx) = args.iter().position(|arg| arg == name) else {
+        return Some(None);
+    };
+    let value = args.get(idx + 1)?.clone();
+    args.drain(idx..=idx + 1);
+    Some(Some(value))
+}
+
+// `--seed <seed>`, `--dumps` and `--record <file>`, then the bot commands
+fn parse_args(mut args: Vec<String>) -> Option<Options> {
+    let seed = match take_option(&mut args, "--seed")? {
+        Some(value) => Some(value.parse().ok()?),
+        None => None,
+    };
+    let record = take_option(&mut args, "--record")?;
+    let dumps = args.iter().any(|arg| arg == "--dumps");
+    args.retain(|arg| arg != "--dumps");
+    (args.len() == 3).then_some(Options {
+        seed,
+        dumps,
+        record,
+        commands: args,
+    })
+}
+
+fn main() {
+    let Some(options) = parse_args(env::args().skip(1).collect()) else {
+        eprintln!(
+            "usage: referee [--seed <seed>] [--dumps] [--record <file>] <bot 0> <bot 1> <bot 2>"
+        );
+        process::exit(2);
+    };
+    let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    if let Err(err) = play_match(&options, seed) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,181 @@
+// Diving simulator, following the referee rules:
+// - the GPU holds the direction expected on each remaining turn
+// - playing the expected direction increases the combo by 1 and scores the new combo
+// - any other action resets the combo to 0
+// - once the sequence runs out, the most points wins
+
+use crate::{
+    action::Action,
+    mini_game::{rank_by, MiniGame},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diving {
+    goals: Vec<char>,
+    points: [i32; 3],
+    combos: [i32; 3],
+}
+
+impl Diving {
+    pub fn new(goals: &str, points: [i32; 3], combos: [i32; 3]) -> Self {
+        Diving {
+            goals: goals.chars().collect(),
+            points,
+            combos,
+        }
+    }
+
+    /// Diving GPU is a sequence of directions, and reg_6 is unused.
+    pub fn matches(gpu: &str, regs: [i32; 7]) -> bool {
+        !gpu.is_empty() && gpu.chars().all(|c| "UDLR".contains(c)) && regs[6] < 0
+    }
+
+    pub fn points(&self) -> [i32; 3] {
+        self.points
+    }
+
+    pub fn combos(&self) -> [i32; 3] {
+        self.combos
+    }
+
+    /// Direction expected this turn, None once the game is over.
+    pub fn goal(&self) -> Option<char> {
+        self.goals.first().copied()
+    }
+
+    pub fn turns_left(&self) -> usize {
+        self.goals.len()
+    }
+
+    /// Points scored and combo reached by `player` with this action.
+    pub fn gain(&self, player: usize, action: Action) -> (i32, i32) {
+        match self.goal() {
+            Some(goal) if action.initial() == goal => {
+                let combo = self.combos[player] + 1;
+                (combo, combo)
+            }
+            _ => (0, 0),
+        }
+    }
+
+    // points and combo of `player` once this action is played
+    fn scored(&self, player: usize, action: Action) -> (i32, i32) {
+        let (points, combo) = self.gain(player, action);
+        (self.points[player] + points, combo)
+    }
+
+    /// Plays every remaining turn with the actions from `policy`,
+    /// returns the end-of-game ranking.
+    pub fn play_out(&self, mut policy: impl FnMut(&Diving) -> [Action; 3]) -> [usize; 3] {
+        let mut diving = self.clone();
+        while !diving.is_over() {
+            diving = diving.play(policy(&diving));
+        }
+        diving.ranking()
+    }
+}
+
+impl MiniGame for Diving {
+    /// reg_0..reg_2 are the points, reg_3..reg_5 the combos.
+    fn parse(gpu: &str, regs: [i32; 7]) -> Option<Self> {
+        let points = [regs[0], regs[1], regs[2]];
+        let combos = [regs[3], regs[4], regs[5]];
+        Self::matches(gpu, regs).then(|| Diving::new(gpu, points, combos))
+    }
+
+    /// Uses up one goal.
+    fn play(&self, actions: [Action; 3]) -> Self {
+        let mut next = self.clone();
+        if self.is_over() {
+            return next;
+        }
+        for (player, action) in actions.iter().enumerate() {
+            (next.points[player], next.combos[player]) = self.scored(player, *action);
+        }
+        next.goals.remove(0);
+        next
+    }
+
+    fn after_move(&self, player: usize, action: Action) -> Self {
+        let mut next = self.clone();
+        if self.is_over() {
+            return next;
+        }
+        (next.points[player], next.combos[player]) = self.scored(player, action);
+        next.goals.remove(0);
+        next
+    }
+
+    fn is_over(&self) -> bool {
+        self.goals.is_empty()
+    }
+
+    /// Most points places best.
+    fn ranking(&self) -> [usize; 3] {
+        rank_by(self.points)
+    }
+
+    fn progress(&self, player: usize) -> i32 {
+        self.points[player] + self.combos[player]
+    }
+
+    fn gpu(&self) -> String {
+        self.goals.iter().collect()
+    }
+
+    fn registers(&self) -> [i32; 7] {
+        let [p0, p1, p2] = self.points;
+        let [c0, c1, c2] = self.combos;
+        [p0, p1, p2, c0, c1, c2, -1]
+    }
+}
+
+#[cfg(test)]
+mod test_diving {
+    use super::*;
+    use crate::action::Action::*;
+
+    #[test]
+    fn parse_registers() {
+        let regs = [10, 3, 0, 4, 0, 1, -1];
+        assert!(Diving::matches("UUDLR", regs));
+        assert!(!Diving::matches("UUDLR", [0, 0, 0, 0, 0, 0, 12]));
+        assert!(!Diving::matches("9914", regs));
+
+        let diving = Diving::parse("UUDLR", regs).unwrap();
+        assert_eq!(diving.points(), [10, 3, 0]);
+        assert_eq!(diving.combos(), [4, 0, 1]);
+        assert_eq!(diving.goal(), Some('U'));
+        assert_eq!(diving.turns_left(), 5);
+    }
+
+    #[test]
+    fn gain_grows_with_combo() {
+        let diving = Diving::new("DL", [10, 3, 0], [4, 0, 1]);
+
+        assert_eq!(diving.gain(0, Down), (5, 5));
+        assert_eq!(diving.gain(1, Down), (1, 1));
+        assert_eq!(diving.gain(2, Up), (0, 0));
+
+        let next = diving.play([Down, Down, Up]);
+        assert_eq!(next.points(), [15, 4, 0]);
+        assert_eq!(next.combos(), [5, 1, 0]);
+        assert_eq!(next.goal(), Some('L'));
+    }
+
+    #[test]
+    fn play_out_returns_ranking() {
+        let diving = Diving::new("UUU", [0, 5, 0], [0, 0, 0]);
+
+        // 1 + 2 + 3 points for the only player following the goals
+        let ranking = diving.play_out(|_| [Up, Down, Left]);
+        assert_eq!(ranking, [0, 1, 2]);
+
+        // missing the last goal costs the most points
+        let ranking = diving.play_out(|d| match d.turns_left() {
+            1 => [Down, Up, Up],
+            _ => [Up, Up, Up],
+        });
+        assert_eq!(ranking, [2, 0, 1]);
+    }
+}
@@ -0,0 +1,248 @@
+// Rolling-horizon evolutionary planner:
+// - a plan is my next HORIZON actions, shared by all running mini-games
+// - plans are scored by playing them through the simulators, the opponents playing greedily,
+//   and ranking every mini-game at the end, finished or not
+// - each generation keeps the best plans, then breeds the rest by crossover and mutation
+// - the better half of the plans survives to the next turn, shifted by one, as a warm start,
+//   so the plans evolved in the first turn's longer allowance seed the turns after
+
+use crate::{
+    action::Action,
+    medal_value::MedalWeights,
+    mini_game::{rank_actions, MiniGame, RunningGame},
+    scoring::placement_points,
+    timer::Budget,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::mem;
+
+pub const HORIZON: usize = 10;
+const POPULATION: usize = 16;
+const ELITES: usize = 2;
+// each action of a child changes with probability 1 / HORIZON
+const MUTATION_RATE: f64 = 1.0 / HORIZON as f64;
+// my progress breaks ties between plans reaching the same placements,
+// small enough to never outweigh a medal
+const PROGRESS_BONUS: f64 = 1e-3;
+
+pub type Plan = [Action; HORIZON];
+
+#[derive(Debug, Clone)]
+pub struct Planner {
+    rng: StdRng,
+    max_generations: u32,
+    // plans of the last turn, best first
+    population: Vec<Plan>,
+}
+
+impl Planner {
+    pub fn new(seed: u64) -> Self {
+        Planner {
+            rng: StdRng::seed_from_u64(seed),
+            max_generations: u32::MAX,
+            population: Vec::new(),
+        }
+    }
+
+    /// Stops evolving after this many generations; one only ranks the seeded population.
+    pub fn with_max_generations(mut self, max_generations: u32) -> Self {
+        self.max_generations = max_generations;
+        self
+    }
+
+    /// Best plan of the last turn, starting with the action it returned.
+    pub fn best_plan(&self) -> Option<&Plan> {
+        self.population.first()
+    }
+
+    /// Evolves plans until the budget runs out, at least one generation, and plays the best one.
+    pub fn next_action(
+        &mut self,
+        games: &[(usize, RunningGame)],
+        weights: &MedalWeights,
+        me: usize,
+        budget: &Budget,
+    ) -> Action {
+        let mut population = self.seed_population();
+        let mut generation = 0;
+        loop {
+            let mut scored: Vec<(f64, Plan)> = population
+                .iter()
+                .map(|plan| (fitness(plan, games, weights, me), *plan))
+                .collect();
+            // stable, so the warm start wins ties
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+            population = scored.iter().map(|(_, plan)| *plan).collect();
+
+            generation += 1;
+            if generation >= self.max_generations || budget.is_over() {
+                break;
+            }
+            population = self.breed(&population);
+        }
+
+        bot_log::debug!(
+            "best plan {:?} after {generation} generations",
+            population[0]
+        );
+        let action = population[0][0];
+        self.population = population;
+        action
+    }
+
+    // better half of the previous plans shifted by one, the rest random
+    fn seed_population(&mut self) -> Vec<Plan> {
+        let previous = mem::take(&mut self.population);
+        let mut population = Vec::with_capacity(POPULATION);
+        for mut plan in previous.into_iter().take(POPULATION / 2) {
+            plan.rotate_left(1);
+            plan[HORIZON - 1] = self.random_action();
+            population.push(plan);
+        }
+        while population.len() < POPULATION {
+            let plan = [(); HORIZON].map(|_| self.random_action());
+            population.push(plan);
+        }
+        population
+    }
+
+    // `ranked` best first
+    fn breed(&mut self, ranked: &[Plan]) -> Vec<Plan> {
+        let mut next = ranked[..ELITES].to_vec();
+        while next.len() < POPULATION {
+            let father = self.tournament(ranked);
+            let mother = self.tournament(ranked);
+            let mut child = father;
+            for (gene, mother_gene) in child.iter_mut().zip(mother) {
+                if self.rng.gen_bool(0.5) {
+                    *gene = mother_gene;
+                }
+            }
+            let child = self.mutate(child);
+            next.push(child);
+        }
+        next
+    }
+
+    // better of two random plans, `ranked` best first
+    fn tournament(&mut self, ranked: &[Plan]) -> Plan {
+        let first = self.rng.gen_range(0..ranked.len());
+        let second = self.rng.gen_range(0..ranked.len());
+        ranked[first.min(second)]
+    }
+
+    fn mutate(&mut self, mut plan: Plan) -> Plan {
+        for gene in plan.iter_mut() {
+            if self.rng.gen_bool(MUTATION_RATE) {
+                *gene = self.random_action();
+            }
+        }
+        plan
+    }
+
+    fn random_action(&mut self) -> Action {
+        *Action::ALL.choose(&mut self.rng).unwrap()
+    }
+}
+
+/// Medal-weighted points of `me` once the plan is played,
+/// unfinished mini-games ranking the players by progress, and my progress breaking ties.
+pub fn fitness(
+    plan: &Plan,
+    games: &[(usize, RunningGame)],
+    weights: &MedalWeights,
+    me: usize,
+) -> f64 {
+    let mut states: Vec<(usize, RunningGame)> = games.to_vec();
+    for &action in plan {
+        if states.iter().all(|(_, game)| game.is_over()) {
+            break;
+        }
+        let mut actions = [0, 1, 2].map(|player| greedy_action(&states, player));
+        actions[me] = action;
+        for (_, game) in states.iter_mut().filter(|(_, game)| !game.is_over()) {
+            *game = game.play(actions);
+        }
+    }
+
+    states
+        .iter()
+        .map(|(idx, game)| {
+            let points = placement_points(game.standing()[me]) as f64;
+            let bonus = game.progress(me) as f64 * PROGRESS_BONUS;
+            (points + bonus) * weights.weight(*idx) as f64
+        })
+        .sum()
+}
+
+// opponent model: best summed rank over the running mini-games
+fn greedy_action(games: &[(usize, RunningGame)], player: usize) -> Action {
+    let running = games
+        .iter()
+        .filter(|(_, game)| !game.is_over())
+        .map(|(_, game)| (game, 1));
+    let ranks = rank_actions(running, player);
+    Action::ALL[(0..4).min_by_key(|&idx| ranks[idx]).unwrap()]
+}
+
+#[cfg(test)]
+mod test_evolution {
+    use super::*;
+    use crate::{action::Action::*, diving::Diving, hurdles::HurdleRace};
+
+    fn planner() -> Planner {
+        Planner::new(3).with_max_generations(30)
+    }
+
+    #[test]
+    fn plans_jumps_ahead() {
+        // RIGHT is the greedy move, but lands on the hurdle after next
+        let race = HurdleRace::new("......#.#.....................", [3, 0, 0], [0; 3]);
+        let games = [(0, RunningGame::Hurdles(race))];
+        let mut planner = planner();
+        let action = planner.next_action(&games, &MedalWeights::uniform(), 0, &Budget::unlimited());
+
+        let plan = planner.best_plan().unwrap();
+        assert_eq!(plan[0], action);
+        assert_ne!(action, Right);
+        let mut race = games[0].1.clone();
+        for &action in &plan[..3] {
+            race = race.after_move(0, action);
+            assert_eq!(race.registers()[3], 0, "stunned by {plan:?}");
+        }
+    }
+
+    #[test]
+    fn follows_diving_combo() {
+        let diving = Diving::new("LLRRDDLLRR", [0; 3], [0; 3]);
+        let games = [(3, RunningGame::Diving(diving))];
+        let mut planner = planner();
+        planner.next_action(&games, &MedalWeights::uniform(), 1, &Budget::unlimited());
+
+        // the opponents play the goals too, tying for gold
+        let plan = planner.best_plan().unwrap();
+        assert_eq!(
+            plan,
+            &[Left, Left, Right, Right, Down, Down, Left, Left, Right, Right]
+        );
+    }
+
+    #[test]
+    fn warm_start_keeps_shifted_plan() {
+        let diving = Diving::new("UDLRUDLRUDLR", [0; 3], [0; 3]);
+        let games = [(3, RunningGame::Diving(diving.clone()))];
+        let mut planner = planner();
+        assert_eq!(
+            planner.next_action(&games, &MedalWeights::uniform(), 0, &Budget::unlimited()),
+            Up
+        );
+
+        let next = [(3, RunningGame::Diving(diving.play([Up; 3])))];
+        let mut planner = planner.with_max_generations(1);
+        assert_eq!(
+            planner.next_action(&next, &MedalWeights::uniform(), 0, &Budget::unlimited()),
+            Down
+        );
+        assert_eq!(planner.best_plan().unwrap()[..3], [Down, Left, Right]);
+    }
+}
@@ -0,0 +1,153 @@
+// Seeded mini-game generator, following the referee constraints:
+// - hurdle tracks are 30 cells, the first 3 clear, never two hurdles side by side
+// - archery wind is 12 to 15 strengths from 0 to 9, every cursor starts on the same cell
+// - roller risk orders are permutations of "UDLR", drawn again every turn
+// - diving sequences are 12 to 16 directions
+// The same seed always replays the same games.
+
+use crate::{
+    action::Action,
+    archery::{Archery, MAX_COORD},
+    diving::Diving,
+    hurdles::HurdleRace,
+    mini_game::RunningGame,
+    roller::Roller,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+pub const TRACK_LENGTH: usize = 30;
+pub const CLEAR_START: usize = 3;
+pub const ROLLER_TURNS: i32 = 15;
+const HURDLE_RATIO: (u32, u32) = (1, 4);
+
+#[derive(Debug, Clone)]
+pub struct Generator {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Generator {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Underlying random source, for draws not covered by the game constraints.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    pub fn hurdle_track(&mut self) -> String {
+        let mut track = ['.'; TRACK_LENGTH];
+        // the finish cell stays clear too
+        for pos in CLEAR_START..TRACK_LENGTH - 1 {
+            let (numerator, denominator) = HURDLE_RATIO;
+            if track[pos - 1] == '.' && self.rng.gen_ratio(numerator, denominator) {
+                track[pos] = '#';
+            }
+        }
+        track.iter().collect()
+    }
+
+    pub fn archery_wind(&mut self) -> String {
+        let length = self.rng.gen_range(12..=15);
+        (0..length)
+            .map(|_| char::from(b'0' + self.rng.gen_range(0..10)))
+            .collect()
+    }
+
+    pub fn archery_start(&mut self) -> (i32, i32) {
+        (
+            self.rng.gen_range(-MAX_COORD..=MAX_COORD),
+            self.rng.gen_range(-MAX_COORD..=MAX_COORD),
+        )
+    }
+
+    pub fn risk_order(&mut self) -> String {
+        let mut order = Action::ALL.map(|action| action.initial());
+        order.shuffle(&mut self.rng);
+        order.iter().collect()
+    }
+
+    pub fn diving_goals(&mut self) -> String {
+        let length = self.rng.gen_range(12..=16);
+        (0..length)
+            .map(|_| Action::ALL.choose(&mut self.rng).unwrap().initial())
+            .collect()
+    }
+
+    /// New run of the mini-game at this index in the turn input.
+    pub fn game(&mut self, idx: usize) -> RunningGame {
+        match idx {
+            0 => RunningGame::Hurdles(HurdleRace::new(&self.hurdle_track(), [0; 3], [0; 3])),
+            1 => {
+                let wind = self.archery_wind();
+                let start = self.archery_start();
+                RunningGame::Archery(Archery::new(&wind, [start; 3]))
+            }
+            2 => {
+                let order = self.risk_order();
+                RunningGame::Roller(Roller::new(&order, [0; 3], [0; 3], ROLLER_TURNS))
+            }
+            _ => RunningGame::Diving(Diving::new(&self.diving_goals(), [0; 3], [0; 3])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_generator {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_games() {
+        let mut first = Generator::new(42);
+        let mut second = Generator::new(42);
+        for idx in 0..4 {
+            assert_eq!(first.game(idx), second.game(idx));
+        }
+        assert_eq!(first.risk_order(), second.risk_order());
+        assert_ne!(
+            Generator::new(1).hurdle_track(),
+            Generator::new(2).hurdle_track()
+        );
+    }
+
+    #[test]
+    fn hurdle_tracks_are_valid() {
+        let mut generator = Generator::new(11);
+        for _ in 0..100 {
+            let track = generator.hurdle_track();
+            assert_eq!(track.len(), TRACK_LENGTH);
+            assert!(HurdleRace::matches(&track));
+            assert!(track.starts_with("..."));
+            assert!(track.ends_with('.'));
+            assert!(!track.contains("##"));
+        }
+    }
+
+    #[test]
+    fn sequences_follow_constraints() {
+        let mut generator = Generator::new(5);
+        for _ in 0..100 {
+            let wind = generator.archery_wind();
+            assert!((12..=15).contains(&wind.len()));
+            assert!(Archery::matches(&wind));
+
+            let (x, y) = generator.archery_start();
+            assert!(x.abs() <= MAX_COORD && y.abs() <= MAX_COORD);
+
+            let order = generator.risk_order();
+            assert!(Roller::matches(&order, [0, 0, 0, 0, 0, 0, ROLLER_TURNS]));
+
+            let goals = generator.diving_goals();
+            assert!((12..=16).contains(&goals.len()));
+            assert!(Diving::matches(&goals, [-1; 7]));
+        }
+    }
+}
@@ -0,0 +1,193 @@
+// Hurdle race simulator, following the referee rules:
+// - LEFT moves 1 space, DOWN 2, RIGHT 3, UP jumps over the next space and moves 2
+// - a move stops at the first hurdle it touches (except the one jumped over)
+// - touching a hurdle stuns the player for the next 3 turns
+// - the race ends as soon as one player reaches the finish
+
+use crate::{
+    action::Action,
+    mini_game::{rank_by, MiniGame},
+};
+
+pub const FINISH: i32 = 29;
+pub const STUN_TURNS: i32 = 3;
+const HURDLE: u8 = b'#';
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HurdleRace {
+    track: Vec<u8>,
+    positions: [i32; 3],
+    stuns: [i32; 3],
+}
+
+impl HurdleRace {
+    pub fn new(track: &str, positions: [i32; 3], stuns: [i32; 3]) -> Self {
+        HurdleRace {
+            track: track.as_bytes().to_vec(),
+            positions,
+            stuns,
+        }
+    }
+
+    /// Hurdle GPU is the racetrack itself.
+    pub fn matches(gpu: &str) -> bool {
+        !gpu.is_empty() && gpu.chars().all(|c| c == '.' || c == '#')
+    }
+
+    pub fn positions(&self) -> [i32; 3] {
+        self.positions
+    }
+
+    pub fn stuns(&self) -> [i32; 3] {
+        self.stuns
+    }
+
+    pub fn position(&self, player: usize) -> i32 {
+        self.positions[player]
+    }
+
+    pub fn stun(&self, player: usize) -> i32 {
+        self.stuns[player]
+    }
+
+    fn is_hurdle(&self, pos: i32) -> bool {
+        self.track.get(pos as usize) == Some(&HURDLE)
+    }
+
+    /// Applies one player's action, returns true if the player hit a hurdle.
+    pub fn step_player(&mut self, player: usize, action: Action) -> bool {
+        if self.stuns[player] > 0 {
+            self.stuns[player] -= 1;
+            return false;
+        }
+
+        let (spaces, mut jumping) = action.hurdle_stride();
+        for _ in 0..spaces {
+            if self.positions[player] >= FINISH {
+                break;
+            }
+            self.positions[player] += 1;
+            if !jumping && self.is_hurdle(self.positions[player]) {
+                self.stuns[player] = STUN_TURNS;
+                return true;
+            }
+            jumping = false;
+        }
+        false
+    }
+
+    /// Players who reached the finish, empty while the race is running.
+    pub fn finishers(&self) -> Vec<usize> {
+        (0..3).filter(|&p| self.positions[p] >= FINISH).collect()
+    }
+
+    /// Distance covered counting the turns lost to a stun as lost RIGHT moves.
+    pub fn effective_position(&self, player: usize) -> i32 {
+        self.positions[player] - self.stuns[player] * 3
+    }
+}
+
+impl MiniGame for HurdleRace {
+    /// reg_0..reg_2 are the positions, reg_3..reg_5 the stun timers.
+    fn parse(gpu: &str, regs: [i32; 7]) -> Option<Self> {
+        let positions = [regs[0], regs[1], regs[2]];
+        let stuns = [regs[3], regs[4], regs[5]];
+        Self::matches(gpu).then(|| HurdleRace::new(gpu, positions, stuns))
+    }
+
+    fn play(&self, actions: [Action; 3]) -> Self {
+        let mut next = self.clone();
+        for (player, action) in actions.iter().enumerate() {
+            next.step_player(player, *action);
+        }
+        next
+    }
+
+    fn after_move(&self, player: usize, action: Action) -> Self {
+        let mut next = self.clone();
+        next.step_player(player, action);
+        next
+    }
+
+    fn is_over(&self) -> bool {
+        self.positions.iter().any(|&pos| pos >= FINISH)
+    }
+
+    fn ranking(&self) -> [usize; 3] {
+        rank_by(self.positions)
+    }
+
+    fn progress(&self, player: usize) -> i32 {
+        self.effective_position(player)
+    }
+
+    fn gpu(&self) -> String {
+        String::from_utf8_lossy(&self.track).into_owned()
+    }
+
+    fn registers(&self) -> [i32; 7] {
+        let [p0, p1, p2] = self.positions;
+        let [s0, s1, s2] = self.stuns;
+        [p0, p1, p2, s0, s1, s2, -1]
+    }
+}
+
+#[cfg(test)]
+mod test_hurdle_race {
+    use super::*;
+    use crate::action::Action::*;
+    const TRACK: &str = ".....#...#...#................";
+
+    #[test]
+    fn moves_stop_at_hurdle() {
+        let race = HurdleRace::new(TRACK, [3, 2, 0], [0; 3]);
+        let next = race.play([Right, Right, Right]);
+
+        assert_eq!(next.positions(), [5, 5, 3]);
+        assert_eq!(next.stuns(), [STUN_TURNS, STUN_TURNS, 0]);
+    }
+
+    #[test]
+    fn parse_registers() {
+        let race = HurdleRace::parse(TRACK, [3, 2, 0, 0, 1, 0, -1]).unwrap();
+        assert_eq!(race.positions(), [3, 2, 0]);
+        assert_eq!(race.stuns(), [0, 1, 0]);
+        assert_eq!(HurdleRace::parse("GAME_OVER", [0; 7]), None);
+    }
+
+    #[test]
+    fn up_clears_hurdle() {
+        let race = HurdleRace::new(TRACK, [4, 3, 7], [0; 3]);
+        let next = race.play([Up, Up, Up]);
+
+        // jumping over the first space only, landing on a hurdle still stuns
+        assert_eq!(next.positions(), [6, 5, 9]);
+        assert_eq!(next.stuns(), [0, STUN_TURNS, STUN_TURNS]);
+    }
+
+    #[test]
+    fn stun_lasts_three_turns() {
+        let mut race = HurdleRace::new(TRACK, [3, 0, 0], [0; 3]);
+        assert!(race.step_player(0, Down));
+
+        for turn in 0..STUN_TURNS {
+            race = race.play([Right, Left, Left]);
+            assert_eq!(race.position(0), 5);
+            assert_eq!(race.stun(0), STUN_TURNS - turn - 1);
+        }
+        race = race.play([Right, Left, Left]);
+        assert_eq!(race.position(0), 8);
+    }
+
+    #[test]
+    fn race_finishes_at_end_of_track() {
+        let race = HurdleRace::new(TRACK, [27, 28, 20], [0; 3]);
+        assert!(!race.is_over());
+
+        let next = race.play([Right, Left, Right]);
+        assert_eq!(next.positions(), [FINISH, FINISH, 23]);
+        assert!(next.is_over());
+        assert_eq!(next.finishers(), vec![0, 1]);
+        assert_eq!(next.ranking(), [0, 0, 2]);
+    }
+}
@@ -0,0 +1,18 @@
+// Simulators shared by the bot and the local referee
+pub mod action;
+pub mod archery;
+pub mod diving;
+pub mod evolution;
+pub mod generator;
+pub mod hurdles;
+pub mod match_state;
+pub mod mcts;
+pub mod medal_value;
+pub mod mini_game;
+pub mod monte_carlo;
+pub mod opponent_model;
+pub mod protocol;
+pub mod referee;
+pub mod roller;
+pub mod scoring;
+pub mod timer;
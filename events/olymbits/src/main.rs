@@ -1,15 +1,58 @@
 #![allow(dead_code)]
-use std::{
-    borrow::BorrowMut,
-    cmp::{self, min, Ordering},
-    collections::{btree_map::Range, HashMap},
-    io,
+use line_reader::{read_or_exit, LineReader};
+use olymbits::{
+    action::Action,
+    evolution::Planner,
+    hurdles::HurdleRace,
+    match_state::MatchState,
+    mcts::Mcts,
+    medal_value::MedalWeights,
+    mini_game::{rank_actions, MiniGame, RunningGame},
+    monte_carlo::MonteCarlo,
+    opponent_model::OpponentModel,
+    protocol::{read_game_line, read_init, read_score_line, GameLine, Init},
+    scoring::PlayerScore,
+    timer::Budget,
 };
+use std::env;
+
+// how the action is chosen, overridden by the first argument when playing locally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strategy {
+    // hurdle safety first, the other mini-games breaking ties
+    Safe,
+    MonteCarlo,
+    Evolution,
+    // decoupled UCT, the opponents searching too
+    Mcts,
+}
 
-macro_rules! parse_input {
-    ($x:expr, $t:ident) => {
-        $x.trim().parse::<$t>().unwrap()
-    };
+impl Strategy {
+    const DEFAULT: Strategy = Strategy::MonteCarlo;
+
+    const ALL: [Strategy; 4] = [
+        Strategy::Safe,
+        Strategy::MonteCarlo,
+        Strategy::Evolution,
+        Strategy::Mcts,
+    ];
+
+    fn from_args() -> Self {
+        let arg = env::args().nth(1);
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| Some(strategy.as_str()) == arg.as_deref())
+            .unwrap_or(Strategy::DEFAULT)
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Strategy::Safe => "safe",
+            Strategy::MonteCarlo => "monte-carlo",
+            Strategy::Evolution => "evolution",
+            Strategy::Mcts => "mcts",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -42,10 +85,9 @@ struct GameTurn {
     game_id: u8,
     run_id: u8,
     track: Option<String>,
-    closest_hurdle: Option<usize>,
     my_player: PlayerTurn,
     other_players: Vec<PlayerTurn>,
-    suitable_move: Option<String>,
+    suitable_move: Option<Action>,
 }
 
 impl GameTurn {
@@ -54,38 +96,12 @@ impl GameTurn {
             game_id,
             run_id,
             track: None,
-            closest_hurdle: None,
             my_player,
             other_players,
             suitable_move: None,
         }
     }
 
-    // move 1 space
-    fn left() -> String {
-        "LEFT".to_owned()
-    }
-
-    // moves 3 spaces
-    fn right() -> String {
-        "RIGHT".to_owned()
-    }
-
-    // jump and move 2 spaces
-    fn up() -> String {
-        "UP".to_owned()
-    }
-
-    // move 2 spaces
-    fn down() -> String {
-        "DOWN".to_owned()
-    }
-
-    // do nothing
-    fn nothing() -> String {
-        "LEFT".to_owned()
-    }
-
     fn add_track(&mut self, track: String) {
         self.track = Some(track);
     }
@@ -98,97 +114,101 @@ impl GameTurn {
         self.my_player = details;
     }
 
-    fn decide(&mut self) -> String {
+    fn decide(&mut self) -> Action {
         if self.track.is_some() {
-            let my_pos = self.my_player.pos as usize;
-
-            // find closest hurdle if unknown
-            if self.closest_hurdle.is_none() {
-                self.closest_hurdle = Some(self.find_closest_hurdle());
-            }
-
-            let closest_hurdle = self.closest_hurdle.unwrap();
-            let dist = closest_hurdle - my_pos;
-            dbg!(dist);
-
-            let to_move = GameTurn::move_based_on_dist(dist);
-            self.suitable_move = Some(to_move.clone());
+            // keep the last of the equally good moves: prefer running over jumping
+            let to_move = Action::ALL
+                .into_iter()
+                .max_by_key(|&mv| self.score_move(mv))
+                .unwrap();
+            self.suitable_move = Some(to_move);
             return to_move;
         }
         self.suitable_move = None;
-        Self::down()
-    }
-
-    // fn find_closest_hurdle(&self) -> usize {
-    //     let track = self.track.as_ref().unwrap();
-    //     let my_pos = self.my_player.pos as usize;
-    //     let ahead_track = &track[my_pos + 1..];
-    //     ahead_track
-    //         .split_once('#')
-    //         .map(|parts| parts.0.len() + my_pos + 1)
-    //         .or_else(|| Some(self.track.as_ref().unwrap().len()))
-    //         .unwrap()
-    // }
-
-    fn find_closest_hurdle(&self) -> usize {
-        let track = self.track.as_ref().unwrap();
-        let my_pos = self.my_player.pos as usize;
-        let mut closest_hurdle = 4;
-        for i in my_pos + 1..(my_pos + 4) {
-            if i < track.len() && track.get(i..i + 1).unwrap() == "#" {
-                closest_hurdle = i - my_pos;
-                break;
-            }
-        }
-        closest_hurdle
+        Action::Down
     }
 
-    fn make_move(my_move: String) {
-        println!("{my_move}");
+    fn my_idx(&self) -> usize {
+        self.my_player.id as usize
     }
 
-    fn move_based_on_dist(dist: usize) -> String {
-        match dist {
-            1 => Self::up(),
-            2 => Self::left(),
-            3 => Self::down(),
-            _ => Self::right(),
+    // race as seen by the referee, players in id order
+    fn race(&self) -> HurdleRace {
+        let mut positions = [0; 3];
+        let mut stuns = [0; 3];
+        for player in self.other_players.iter().chain([&self.my_player]) {
+            positions[player.id as usize] = player.pos;
+            stuns[player.id as usize] = player.stun;
         }
+        HurdleRace::new(self.track.as_deref().unwrap_or_default(), positions, stuns)
     }
 
-    fn move_to_value(this_move: &String) -> u8 {
-        match this_move.as_str() {
-            "LEFT" => 1,
-            "UP" => 2,
-            "DOWN" => 2,
-            "RIGHT" => 3,
-            _ => 99,
-        }
+    // my race state once I play this move, other players standing still
+    fn simulate_move(&self, this_move: Action) -> HurdleRace {
+        self.race().after_move(self.my_idx(), this_move)
     }
 
-    fn value_to_move(val: u8) -> String {
-        match val {
-            1 => "LEFT".to_owned(),
-            2 => "DOWN".to_owned(),
-            3 => "RIGHT".to_owned(),
-            _ => "NOTHING".to_owned(),
-        }
+    fn stumbles_with(&self, this_move: Action) -> bool {
+        self.race().step_player(self.my_idx(), this_move)
+    }
+
+    fn score_move(&self, this_move: Action) -> i32 {
+        self.simulate_move(this_move)
+            .effective_position(self.my_idx())
     }
 
-    // Always take right unless you are just 1 space from hurdle
-    // - on avg gain 1 space over the case where we avoid hurdle
-    fn aggressive_strategy(closest_hurdle: usize) -> String {
-        let mut chosen_mv = GameTurn::up();
-        if closest_hurdle != 1{
-            chosen_mv = GameTurn::right();
+    fn make_move(my_move: Action) {
+        println!("{my_move}");
+    }
+
+    // my distance to the best other player, negative when I'm behind
+    fn lead(&self, my_pos: i32) -> i32 {
+        let best_other = self.other_players.iter().map(|p| p.pos).max();
+        best_other.map_or(0, |other_pos| my_pos - other_pos)
+    }
+
+    // stumble:
+    // 1. If my jump in one game, leads to stumble in another
+    // 2. If non-jump move in one game, leads to stumble in another
+    // each stumble costs the medal weight of its game
+    fn stumble_cost(game_turns: &[Self], this_move: Action, weights: &MedalWeights) -> u32 {
+        game_turns
+            .iter()
+            .filter(|game_turn| game_turn.stumbles_with(this_move))
+            .map(|game_turn| weights.weight(game_turn.game_id as usize))
+            .sum()
+    }
+
+    // safest hurdle move, unless another move stumbles as little
+    // and ranks better across all the running mini-games,
+    // each given with its index in the score line
+    fn shared_approach(
+        game_turns: &mut [Self],
+        games: &[(usize, RunningGame)],
+        weights: &MedalWeights,
+        my_idx: usize,
+    ) -> Action {
+        let safest_move = GameTurn::safe_approach(game_turns, weights);
+        if games.is_empty() {
+            return safest_move;
         }
-        chosen_mv
+
+        let weighted_games = games.iter().map(|(id, game)| (game, weights.weight(*id)));
+        let ranks = rank_actions(weighted_games, my_idx);
+        let least_cost = GameTurn::stumble_cost(game_turns, safest_move, weights);
+        Action::ALL
+            .into_iter()
+            .zip(ranks)
+            .filter(|&(mv, _)| GameTurn::stumble_cost(game_turns, mv, weights) == least_cost)
+            .min_by_key(|(mv, rank)| (*rank, *mv != safest_move))
+            .map(|(mv, _)| mv)
+            .unwrap()
     }
 
     // try to avoid every hurdle
-    fn safe_approach(game_turns: &mut [Self]) -> String {
+    fn safe_approach(game_turns: &mut [Self], weights: &MedalWeights) -> Action {
         let mut moves = Vec::new();
-        let mut stumbles: Vec<u8> = Vec::new();
+        let mut stumbles: Vec<u32> = Vec::new();
 
         // find each game's most suited move
         for game_turn in game_turns.iter_mut() {
@@ -198,257 +218,34 @@ impl GameTurn {
 
         // Choose the move which stumbles the least
         for this_move in moves.iter() {
-            let mut num_stumbles = 0;
-
-            // analyse how much stumble is caused by this move
-            for game_turn in game_turns.iter() {
-                let my_pos = game_turn.my_player.pos;
-                let my_new_pos = my_pos + GameTurn::move_to_value(this_move) as i32;
-                let hurdle_pos = game_turn.closest_hurdle.unwrap() as i32;
-                let mv_is_up = *this_move == GameTurn::up();
-
-                // stumble:
-                // 1. If my jump in one game, leads to stumble in another
-                // 2. If non-jump move in one game, leads to stumble in another
-                if (my_new_pos >= hurdle_pos && !mv_is_up) || (mv_is_up && my_new_pos == hurdle_pos)
-                {
-                    num_stumbles += 1;
-                }
-                // think of how any additional stumble in this game run affects
-            }
-            stumbles.push(num_stumbles);
-        }
-
-        let info = stumbles.iter().enumerate().min();
-        if info.is_none() {
-            return GameTurn::right();
+            stumbles.push(GameTurn::stumble_cost(game_turns, *this_move, weights));
         }
 
-        let (best_mv_id, least_stumbles) = info.unwrap();
+        let Some(&least_stumbles) = stumbles.iter().min() else {
+            return Action::Right;
+        };
         let best_moves_id: Vec<usize> = stumbles
             .iter()
             .enumerate()
-            .filter_map(|(i, v)| if v == least_stumbles { Some(i) } else { None })
+            .filter_map(|(i, &v)| if v == least_stumbles { Some(i) } else { None })
             .collect();
 
-        let mv_count = best_moves_id.len();
-        if mv_count == 1 {
-            moves.get(best_mv_id).unwrap().to_owned()
-        } else {
-            // all the best move cause same amount of stumble,
-            let mut move_id = 0;
-
-            // 1- then choose the one which moves more distance
-            {
-                let max_mv_val = best_moves_id
-                    .iter()
-                    .map(|id| GameTurn::move_to_value(moves.get(*id).unwrap()))
-                    .max()
-                    .unwrap();
-
-                move_id = *best_moves_id
-                    .iter()
-                    .find(|&id| GameTurn::move_to_value(moves.get(*id).unwrap()) == max_mv_val)
-                    .unwrap();
-            }
-
-            // 2- then choose the one which is best for the game where we are not in the lead
-            {
-                let mut weakest_lead = 0;
-                let mut weakest_id = 0;
-                game_turns.iter().enumerate().for_each(|(id, game_turn)| {
-                    let my_pos = game_turn.my_player.pos;
-                    let p2 = game_turn.other_players[0].pos;
-                    let p3 = game_turn.other_players[1].pos;
-                    let mut game_lead = 0;
-
-                    let mut positions = [my_pos, p2, p3];
-                    positions.sort();
-
-                    if my_pos == positions[2] {
-                        game_lead = my_pos - positions[1];
-                    } else {
-                        game_lead = my_pos - positions[2];
-                    }
-
-                    if game_lead < weakest_lead {
-                        weakest_lead = game_lead;
-                        weakest_id = id;
-                    }
-                });
-                move_id = weakest_id;
-            }
-
-            moves[move_id].clone()
-        }
-    }
-
-    // decide based on avg progress
-    fn progress_approach(game_turns: &mut [Self]) -> String {
-        let mut moves = Vec::new();
-        let mut avg_progresses = Vec::new();
-
-        // storing curr moves each of which best suit atleast one game
-        for game_turn in game_turns.iter_mut() {
-            if game_turn.track.is_some() {
-                let curr_move = game_turn.decide();
-                moves.push(curr_move);
-            }
-        }
-
-        // Calculate avg progress for each move and store it
-        // - take note of stumbleness
-        // - take note of difference in lead due to move
-        for this_move in moves.iter() {
-            let mut num_stumbles = 0;
-            let mut avg_progess = GameTurn::move_to_value(this_move) as i32;
-            let mut extra_leads = 0;
-
-            for game_turn in game_turns.iter() {
-                if game_turn.track.is_some() {
-                    let mut game_lead = 0;
-                    let mut new_game_lead = 0;
-                    let my_pos = game_turn.my_player.pos;
-                    let my_new_pos = my_pos + GameTurn::move_to_value(this_move) as i32;
-                    let hurdle_pos = game_turn.closest_hurdle.unwrap() as i32;
-                    let mv_is_up = *this_move == GameTurn::up();
-                    let mut stumbled = false;
-
-                    // stumbles affecting progress
-                    if (my_new_pos >= hurdle_pos && !mv_is_up)
-                        || (mv_is_up && my_new_pos == hurdle_pos)
-                    {
-                        num_stumbles += 1;
-                        stumbled = true;
-                    }
-
-                    // progress compared to peers should be decent
-                    let p2 = game_turn.other_players[0].pos;
-                    let p3 = game_turn.other_players[1].pos;
-
-                    let mut positions = [my_pos, p2, p3];
-                    positions.sort();
-
-                    if my_pos == positions[2] {
-                        game_lead = my_pos - positions[1];
-                    } else {
-                        game_lead = my_pos - positions[2];
-                    }
-
-                    if stumbled {
-                        if hurdle_pos == positions[2] {
-                            new_game_lead = hurdle_pos - positions[1];
-                        } else {
-                            new_game_lead = hurdle_pos - positions[2];
-                        }
-                    } else if my_new_pos == positions[2] {
-                        new_game_lead = my_new_pos - positions[1];
-                    } else {
-                        new_game_lead = my_new_pos - positions[2];
-                    }
-
-                    extra_leads += new_game_lead - game_lead;
-                }
-            }
-            // Calculate avg progress for each move
-            let avg_leads = extra_leads / game_turns.len() as i32;
-            avg_progess = avg_progess - (num_stumbles as i32 * 10 / 4) + avg_leads;
-            avg_progresses.push(avg_progess);
-        }
-
-        // find highest value of progress
-        let best_progress = avg_progresses.iter().max();
-        if best_progress.is_none() {
-            return GameTurn::up();
-        }
-        let best_progress = best_progress.unwrap();
-
-        // find all moves with best progress
-        let move_ids: Vec<usize> = avg_progresses
+        // all the best move cause same amount of stumble,
+        // 1- then choose the one which moves more distance
+        let max_mv_val = best_moves_id
             .iter()
-            .enumerate()
-            .filter(|(_, &prog)| prog == *best_progress)
-            .map(|(move_id, _)| move_id)
-            .collect();
-
-        let chosen_mv_id = move_ids.first().unwrap();
-        moves.get(*chosen_mv_id).unwrap().to_owned()
-    }
-}
-
-#[derive(Debug, Default, Clone)]
-struct MiniGameScore {
-    game_id: u8,
-    score: u32,
-    gold: u8,
-    silver: u8,
-    bronze: u8,
-    latest_run_turn: GameTurn,
-}
-
-#[derive(Debug, Default, Clone)]
-struct GameRun {
-    game_id: u8,
-    turns: Vec<GameTurn>,
-}
-
-impl GameRun {
-    fn new(game_id: u8) -> Self {
-        GameRun {
-            game_id,
-            turns: Vec::new(),
-        }
-    }
-}
-
-#[derive(Debug, Default, Clone)]
-struct TotalPlayerInfo {
-    player_id: i32,
-    gold: u32,
-    silver: u32,
-    bronze: u32,
-    final_score: u32,
-    games_info: Vec<MiniGameScore>,
-}
-
-impl TotalPlayerInfo {
-    fn new(id: i32, final_score: u32, gold: u32, silver: u32, bronze: u32) -> Self {
-        TotalPlayerInfo {
-            player_id: id,
-            final_score,
-            gold,
-            silver,
-            bronze,
-            games_info: Vec::new(),
-        }
-    }
-
-    fn calculate_final_score(&mut self) {
-        self.final_score = self
-            .games_info
-            .iter()
-            .fold(1u32, |acc, game| acc * game.score);
-    }
-
-    fn add_gold_medal(&mut self, game_id: u8) {
-        self.gold += 1;
-        self.games_info[game_id as usize].gold += 1;
-        self.calculate_final_score();
-    }
-
-    fn add_silver_medal(&mut self, game_id: u8) {
-        self.silver += 1;
-        self.games_info[game_id as usize].silver += 1;
-        self.calculate_final_score();
-    }
-
-    fn add_bronze_medal(&mut self, game_id: u8) {
-        self.bronze += 1;
-        self.games_info[game_id as usize].bronze += 1;
-    }
-
-    fn add_game_score(&mut self, game_id: usize, score: u32) {
-        self.games_info[game_id].score += score;
+            .map(|&id| moves[id].hurdle_spaces())
+            .max()
+            .unwrap();
+        let farthest_moves_id = best_moves_id
+            .into_iter()
+            .filter(|&id| moves[id].hurdle_spaces() == max_mv_val);
+
+        // 2- then choose the one which is best for the game where we are not in the lead
+        let move_id = farthest_moves_id
+            .min_by_key(|&id| game_turns[id].lead(game_turns[id].my_player.pos))
+            .unwrap();
+        moves[move_id]
     }
 }
 
@@ -457,137 +254,174 @@ impl TotalPlayerInfo {
  * the standard input according to the problem statement.
  **/
 fn main() {
-    // get my player id
-    let mut input_line = String::new();
-    io::stdin().read_line(&mut input_line).unwrap();
-    let player_idx = parse_input!(input_line, i32);
-
-    // the number of simultaneously running mini-game_turn = 4 (depends)
-    let mut input_line = String::new();
-    io::stdin().read_line(&mut input_line).unwrap();
-    let nb_games = parse_input!(input_line, i32);
-
-    let mut num_runs = 0;
+    let mut input = LineReader::stdin();
+    // my player id, then the number of simultaneously running mini-games
+    let Init {
+        player: player_idx,
+        nb_games,
+    } = read_or_exit(read_init(&mut input));
+
+    let mut match_state = MatchState::new();
+    let strategy = Strategy::from_args();
+    bot_log::info!("player {player_idx}, {nb_games} mini-games, {strategy:?} strategy");
+    let mut monte_carlo = MonteCarlo::new(player_idx as u64);
+    let mut planner = Planner::new(player_idx as u64);
+    let mut mcts = Mcts::new(player_idx as u64);
+    let mut opponents = OpponentModel::new();
     // game_turn loop
     loop {
         // get score info of each player before start of game_turns
-        let mut player_infos: Vec<TotalPlayerInfo> = Vec::new();
-        for i in 0..3 as usize {
-            // contains a breakdown of each player's final score
-            let mut input_line = String::new();
-            io::stdin().read_line(&mut input_line).unwrap();
-            let score_info = input_line.trim_matches('\n').to_string();
-            let inputs = score_info.split(" ").collect::<Vec<_>>();
-
-            let final_score = parse_input!(inputs[0], u32);
-            let nb_gold_medals = parse_input!(inputs[1], u32);
-            let nb_silver_medals = parse_input!(inputs[2], u32);
-            let nb_bronze_medals = parse_input!(inputs[3], u32);
-
-            let player_info = TotalPlayerInfo::new(
-                i as i32,
-                final_score,
-                nb_gold_medals,
-                nb_silver_medals,
-                nb_bronze_medals,
-            );
-            player_infos.push(player_info);
+        let mut player_infos: Vec<PlayerScore> = Vec::new();
+        let mut budget = None;
+        for _ in 0..3 {
+            // contains a breakdown of each player's final score:
+            // final score, then gold silver bronze of every mini-game
+            let score = read_or_exit(read_score_line(&mut input));
+            // the budget starts at the first score line, and is 1s on turn 0
+            budget.get_or_insert_with(|| Budget::for_turn(!match_state.is_started()));
+            player_infos.push(score);
         }
+        let budget = budget.unwrap();
 
         // choose my move for all 4 game_turns
         let mut my_game_turns: Vec<GameTurn> = Vec::new();
+        let mut running_games: Vec<(usize, RunningGame)> = Vec::new();
+        let weights = MedalWeights::new(&player_infos, player_idx);
         // let mut mini_games: Vec<MiniGamesScore> = [0, 1, 2, 3u8].iter().map(|&i| ).collect();
 
-        let mut closest_hurdle = 4;
-
-        // start each mini-game_turn:
-        // - multiple runs in one mini-game
-        // - multiple turns in one run
-        for _i in 0..nb_games as usize {
-            let mut input_line = String::new();
-            io::stdin().read_line(&mut input_line).unwrap();
-            let inputs = input_line.split(' ').collect::<Vec<_>>();
-
-            // GPU: ASCII representation of the racetrack (GAME_OVER during reset turn)
-            let gpu = inputs[0].trim().to_string();
-            // position of player 1
-            let reg_0 = parse_input!(inputs[1], i32);
-            // position of player 2
-            let reg_1 = parse_input!(inputs[2], i32);
-            // position of player 3
-            let reg_2 = parse_input!(inputs[3], i32);
-            // stun time for player 1
-            let reg_3 = parse_input!(inputs[4], i32);
-            // stun time for player 2
-            let reg_4 = parse_input!(inputs[5], i32);
-            // stun time for player 3
-            let reg_5 = parse_input!(inputs[6], i32);
-            // unused = -1
-            let reg_6 = parse_input!(inputs[7], i32);
-
-            let positions = [reg_0, reg_1, reg_2];
-            let stuns = [reg_3, reg_4, reg_5];
-            let ids = [0, 1, 2];
-
-            let mut players = vec![];
-            for _i in ids {
-                let p = PlayerTurn {
-                    id: _i,
-                    pos: positions[_i as usize],
-                    stun: stuns[_i as usize],
-                };
-                players.push(p);
-            }
-            let my_player = players.remove(player_idx as usize);
-
-            if gpu != "GAME_OVER" && my_player.stun == 0 {
-                let mut game_turn = GameTurn::new(_i as u8, num_runs, my_player, players);
-                game_turn.add_track(gpu);
-                closest_hurdle = min(closest_hurdle, game_turn.find_closest_hurdle());
-                my_game_turns.push(game_turn);
-            } else if gpu == "GAME_OVER" {
-                // GAME_OVER => Game run has finished, get medals and maybe start a game run
-                num_runs += 1;
-                my_game_turns.clear();
+        // GPU: ASCII representation of the racetrack (GAME_OVER during reset turn)
+        // or the wind strengths in archery, the risk order in roller, the goals in diving;
+        // in the race, reg_0..reg_2 are the positions and reg_3..reg_5 the stun times
+        let lines: Vec<GameLine> = (0..nb_games)
+            .map(|_| read_or_exit(read_game_line(&mut input)))
+            .collect();
+        for (game_id, line) in lines.iter().enumerate() {
+            if let Some(game) = RunningGame::parse(&line.gpu, line.regs) {
+                running_games.push((game_id, game));
             }
         }
-        let safest_move = GameTurn::safe_approach(&mut my_game_turns);
-        GameTurn::make_move(safest_move);
+        // a mini-game in reset only drops its own turn
+        my_game_turns.extend(hurdle_turns(&lines, player_idx));
+        let me = player_idx;
+        match_state.update(&player_infos, &running_games);
+        for game_turn in my_game_turns.iter_mut() {
+            let runs = match_state.runs(game_turn.game_id as usize).len();
+            game_turn.set_run_id(runs.saturating_sub(1) as u8);
+        }
+        monte_carlo.set_turns_left(match_state.turns_left());
+        mcts.set_turns_left(match_state.turns_left());
+        opponents.observe(&running_games);
+        monte_carlo.set_policies(opponents.distributions(me));
+        mcts.set_policies(opponents.distributions(me));
+        mcts.advance(opponents.last_actions());
+        // every mini-game resetting: nothing to search
+        let chosen = match strategy {
+            _ if running_games.is_empty() => Action::Right,
+            Strategy::Safe => {
+                GameTurn::shared_approach(&mut my_game_turns, &running_games, &weights, me)
+            }
+            Strategy::MonteCarlo => monte_carlo.best_action(&running_games, &weights, me, &budget),
+            Strategy::Evolution => planner.next_action(&running_games, &weights, me, &budget),
+            Strategy::Mcts => {
+                let all_weights = [0, 1, 2].map(|player| MedalWeights::new(&player_infos, player));
+                mcts.search(&running_games, &all_weights, me, &budget)
+            }
+        };
+        let finals = player_infos
+            .iter()
+            .map(|score| score.final_score.to_string());
+        bot_log::turn_dump!(
+            match_state.turn(),
+            strategy = strategy.as_str(),
+            action = chosen,
+            elapsed_ms = budget.elapsed().as_millis(),
+            finals = finals.collect::<Vec<_>>().join(","),
+            weights = format!("{:?}", weights.weights()).replace(' ', ""),
+            games = running_games.len(),
+        );
+        GameTurn::make_move(chosen);
     }
 }
 
+// my turn in each hurdle race running, none while I am stunned
+fn hurdle_turns(lines: &[GameLine], player_idx: usize) -> Vec<GameTurn> {
+    let races = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| HurdleRace::matches(&line.gpu));
+    races
+        .filter_map(|(game_id, line)| {
+            let mut players: Vec<PlayerTurn> = (0..3)
+                .map(|id| PlayerTurn::new(line.regs[id], line.regs[id + 3], id as i32))
+                .collect();
+            let my_player = players.remove(player_idx);
+            if my_player.stun != 0 {
+                return None;
+            }
+            let mut game_turn = GameTurn::new(game_id as u8, 0, my_player, players);
+            game_turn.add_track(line.gpu.clone());
+            Some(game_turn)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test_hurdle_up {
     use rand::Rng;
 
     use super::*;
-    const RIGHT: &str = "RIGHT";
-    const UP: &str = "UP";
-    const DOWN: &str = "DOWN";
-    const LEFT: &str = "LEFT";
+    use olymbits::{
+        archery::Archery, diving::Diving, generator::Generator, roller::Roller,
+        scoring::MiniGameScore,
+    };
     const TRACK1: &str = ".....#...#...#................";
     const TRACK2: &str = ".....#...#...#................";
     const TRACK3: &str = ".....#...#...#................";
     const TRACK4: &str = ".....#...#...#................";
 
+    #[test]
+    fn hurdle_turn_survives_another_game_over() {
+        let line = |gpu: &str, regs| GameLine {
+            gpu: gpu.to_string(),
+            regs,
+        };
+        let lines = [
+            line(TRACK1, [2, 0, 5, 0, 0, 0, -1]),
+            line("GAME_OVER", [-1; 7]),
+            line("GAME_OVER", [-1; 7]),
+            line("UUDLR", [0, 0, 0, 0, 0, 0, -1]),
+        ];
+        let turns = hurdle_turns(&lines, 0);
+        assert_eq!(turns.len(), 1);
+        assert_eq!((turns[0].game_id, turns[0].my_player.pos), (0, 2));
+        assert_eq!(turns[0].track.as_deref(), Some(TRACK1));
+
+        // the race itself in reset, or me stunned: no turn
+        let lines = [
+            line("GAME_OVER", [-1; 7]),
+            line(TRACK2, [2, 0, 5, 0, 0, 0, -1]),
+        ];
+        assert_eq!(hurdle_turns(&lines, 0)[0].game_id, 1);
+        assert!(hurdle_turns(&[line(TRACK3, [2, 0, 5, 1, 0, 0, -1])], 0).is_empty());
+    }
+
     #[test]
     fn right_closest_hurdle() {
         let track = TRACK1.to_string();
         let my_pos = 2;
         let mut h_pos = 4;
-        for i in my_pos+1..(my_pos+4){
-            if i < track.len() && track.get(i..i+1).unwrap() == "#"{
+        for i in my_pos + 1..(my_pos + 4) {
+            if i < track.len() && track.get(i..i + 1).unwrap() == "#" {
                 h_pos = i - my_pos;
                 break;
             }
         }
         assert_eq!(h_pos, 3);
-        
 
         let my_pos = 7;
         let mut h_pos = 4;
-        for i in my_pos+1..(my_pos+4){
-            if i < track.len() && track.get(i..i+1).unwrap() == "#"{
+        for i in my_pos + 1..(my_pos + 4) {
+            if i < track.len() && track.get(i..i + 1).unwrap() == "#" {
                 h_pos = i - my_pos;
                 break;
             }
@@ -610,18 +444,16 @@ mod test_hurdle_up {
         let mut game_turn = GameTurn::default();
         game_turn.add_track(track);
         game_turn.add_player_details(details);
-        dbg!(game_turn.find_closest_hurdle());
 
-        assert_eq!(game_turn.decide(), UP.to_string());
+        assert_eq!(game_turn.decide(), Action::Up);
 
-        let details = PlayerTurn::new(29, 0, 0);
+        let details = PlayerTurn::new(12, 0, 0);
         let track = TRACK1.to_string();
         let mut game_turn = GameTurn::default();
         game_turn.add_track(track);
         game_turn.add_player_details(details);
 
-        dbg!(game_turn.find_closest_hurdle());
-        assert_eq!(game_turn.decide(), UP.to_string());
+        assert_eq!(game_turn.decide(), Action::Up);
     }
 
     #[test]
@@ -631,9 +463,8 @@ mod test_hurdle_up {
         let mut game_turn = GameTurn::default();
         game_turn.add_track(track);
         game_turn.add_player_details(details);
-        dbg!(game_turn.find_closest_hurdle());
 
-        assert_eq!(game_turn.decide(), DOWN.to_string());
+        assert_eq!(game_turn.decide(), Action::Down);
         GameTurn::make_move(game_turn.decide());
     }
 
@@ -644,9 +475,8 @@ mod test_hurdle_up {
         let mut game_turn = GameTurn::default();
         game_turn.add_track(track);
         game_turn.add_player_details(details);
-        dbg!(game_turn.find_closest_hurdle());
 
-        assert_eq!(game_turn.decide(), LEFT.to_string());
+        assert_eq!(game_turn.decide(), Action::Left);
     }
 
     #[test]
@@ -657,8 +487,7 @@ mod test_hurdle_up {
         game_turn.add_track(track);
         game_turn.add_player_details(details);
 
-        dbg!(game_turn.find_closest_hurdle());
-        assert_eq!(game_turn.decide(), RIGHT.to_string());
+        assert_eq!(game_turn.decide(), Action::Right);
 
         let details = PlayerTurn::new(14, 0, 0);
         let track = TRACK1.to_string();
@@ -666,27 +495,118 @@ mod test_hurdle_up {
         game_turn.add_track(track);
         game_turn.add_player_details(details);
 
-        dbg!(game_turn.find_closest_hurdle());
-        assert_eq!(game_turn.decide(), RIGHT.to_string());
+        assert_eq!(game_turn.decide(), Action::Right);
     }
 
     #[test]
     fn safest_move_in_all_games() {
-        let mut game_turns = create_multiple_games(3);
-        dbg!(game_turns.clone());
-        let safest_move = GameTurn::safe_approach(&mut game_turns);
-        dbg!(safest_move);
+        let mut generator = Generator::new(17);
+        let mut game_turns = create_multiple_games(&mut generator, 3);
+        // the second race has a hurdle right ahead, only UP clears it
+        assert_eq!(
+            game_turns[1].track.as_deref(),
+            Some("...#.#..#.#..#.....#.#........")
+        );
+        assert_eq!(game_turns[1].my_player.pos, 4);
+        let safest_move = GameTurn::safe_approach(&mut game_turns, &MedalWeights::uniform());
+        assert_eq!(safest_move, Action::Up);
+        assert!(game_turns
+            .iter()
+            .all(|game_turn| !game_turn.stumbles_with(safest_move)));
     }
 
-    fn create_multiple_games(n: u32) -> Vec<GameTurn> {
-        let track = TRACK1.to_string();
+    #[test]
+    fn safest_move_avoids_stumble() {
+        let mut game_turns = Vec::new();
+        for pos in [0, 2] {
+            let mut game_turn = GameTurn::default();
+            game_turn.add_track(TRACK1.to_string());
+            game_turn.add_player_details(PlayerTurn::new(pos, 0, 0));
+            game_turns.push(game_turn);
+        }
+
+        // RIGHT is best in the first game but hits the hurdle in the second
+        let chosen = GameTurn::safe_approach(&mut game_turns, &MedalWeights::uniform());
+        assert_eq!(chosen, Action::Down);
+    }
+
+    #[test]
+    fn stumble_in_lighter_game() {
+        let mut game_turns = Vec::new();
+        for (game_id, pos) in [(0, 4), (1, 3)] {
+            let mut game_turn = GameTurn::new(game_id, 0, PlayerTurn::new(pos, 0, 0), vec![]);
+            game_turn.add_track(TRACK1.to_string());
+            game_turns.push(game_turn);
+        }
+
+        // UP stumbles in the second game, LEFT in the first one
+        let gold = MiniGameScore::new(1, 0, 0);
+        let weak_second = PlayerScore::new([gold, MiniGameScore::default(), gold, gold]);
+        let weights = MedalWeights::new(&[weak_second], 0);
+        let chosen = GameTurn::safe_approach(&mut game_turns, &weights);
+        assert_eq!(chosen, Action::Left);
+
+        let weak_first = PlayerScore::new([MiniGameScore::default(), gold, gold, gold]);
+        let weights = MedalWeights::new(&[weak_first], 0);
+        let chosen = GameTurn::safe_approach(&mut game_turns, &weights);
+        assert_eq!(chosen, Action::Up);
+    }
+
+    #[test]
+    fn archery_breaks_hurdle_ties() {
+        let mut game_turn = GameTurn::default();
+        game_turn.add_track(TRACK1.to_string());
+        game_turn.add_player_details(PlayerTurn::new(20, 0, 0));
+        let mut game_turns = vec![game_turn];
+
+        // every move is safe on the track, the wind pushes the cursor back to the target
+        let archery = Archery::new("5", [(0, 5), (0, 0), (0, 0)]);
+        let games = [(1, RunningGame::Archery(archery))];
+        let weights = MedalWeights::uniform();
+        let chosen = GameTurn::shared_approach(&mut game_turns, &games, &weights, 0);
+        assert_eq!(chosen, Action::Up);
+
+        let chosen = GameTurn::shared_approach(&mut game_turns, &[], &weights, 0);
+        assert_eq!(chosen, Action::Right);
+    }
+
+    #[test]
+    fn roller_breaks_hurdle_ties() {
+        let mut game_turn = GameTurn::default();
+        game_turn.add_track(TRACK1.to_string());
+        game_turn.add_player_details(PlayerTurn::new(20, 0, 0));
+        let mut game_turns = vec![game_turn];
+
+        // LEFT and DOWN would reach risk 5 and get stunned, UP is the fastest safe move
+        let roller = Roller::new("RULD", [0, 5, 7], [4, 0, 0], 10);
+        let games = [(2, RunningGame::Roller(roller))];
+        let weights = MedalWeights::uniform();
+        let chosen = GameTurn::shared_approach(&mut game_turns, &games, &weights, 0);
+        assert_eq!(chosen, Action::Up);
+    }
+
+    #[test]
+    fn diving_breaks_hurdle_ties() {
+        let mut game_turn = GameTurn::default();
+        game_turn.add_track(TRACK1.to_string());
+        game_turn.add_player_details(PlayerTurn::new(20, 0, 0));
+        let mut game_turns = vec![game_turn];
+
+        let diving = Diving::new("LUD", [0, 0, 0], [2, 0, 0]);
+        let games = [(3, RunningGame::Diving(diving))];
+        let weights = MedalWeights::uniform();
+        let chosen = GameTurn::shared_approach(&mut game_turns, &games, &weights, 0);
+        assert_eq!(chosen, Action::Left);
+    }
+
+    fn create_multiple_games(generator: &mut Generator, n: u32) -> Vec<GameTurn> {
         let mut game_turns = Vec::new();
-        let mut rng = rand::thread_rng();
         for i in 0..n {
+            let track = create_random_track(generator);
             let mut game_turn = GameTurn::default();
-            let a = rng.gen_range(0..5);
+            let a = generator.rng().gen_range(0..5);
             let details = PlayerTurn::new(a, 0, 0);
-            game_turn.add_track(track.clone());
+            game_turn.add_track(track);
             game_turn.set_run_id(i as u8);
             game_turn.add_player_details(details.clone());
             game_turns.push(game_turn);
@@ -695,11 +615,14 @@ mod test_hurdle_up {
     }
 
     // - no two ## next to each other
-    fn create_random_track() {}
+    fn create_random_track(generator: &mut Generator) -> String {
+        generator.hurdle_track()
+    }
 
-    fn create_random_player_turn(my_pos: usize) -> PlayerTurn {
-        let mut rng = rand::thread_rng();
-        let a = rng.gen_range(my_pos - 3..my_pos + 3);
+    fn create_random_player_turn(generator: &mut Generator, my_pos: usize) -> PlayerTurn {
+        let a = generator
+            .rng()
+            .gen_range(my_pos.saturating_sub(3)..my_pos + 3);
         PlayerTurn::new(a as i32, 0, 0)
     }
 }
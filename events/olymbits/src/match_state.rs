@@ -0,0 +1,209 @@
+// Match state kept for the whole match:
+// - every mini-game goes through runs, each one ending with a GAME_OVER turn
+// - a run records the state observed on each of its turns
+// - the medals of a run are read from the score lines of its GAME_OVER turn
+// - the match lasts MAX_TURNS turns, a run still going at the end hands out nothing
+
+use crate::{
+    mini_game::RunningGame,
+    protocol::MAX_TURNS,
+    scoring::{PlayerScore, NB_MINI_GAMES},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRun {
+    /// Index of the mini-game in the turn input.
+    pub game: usize,
+    /// Match turn of the first observed state.
+    pub start_turn: usize,
+    /// State observed on each turn of the run, in order.
+    pub states: Vec<RunningGame>,
+    /// Placement of each player once the run is over, 0 being gold.
+    pub medals: Option<[usize; 3]>,
+    finished: bool,
+}
+
+impl GameRun {
+    fn new(game: usize, start_turn: usize) -> Self {
+        GameRun {
+            game,
+            start_turn,
+            states: Vec::new(),
+            medals: None,
+            finished: false,
+        }
+    }
+
+    /// Turn index within the run, 0 on its first turn.
+    pub fn turn(&self) -> usize {
+        self.states.len().saturating_sub(1)
+    }
+
+    pub fn last_state(&self) -> Option<&RunningGame> {
+        self.states.last()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MatchState {
+    // turns observed so far
+    turn: usize,
+    scores: Vec<PlayerScore>,
+    runs: [Vec<GameRun>; NB_MINI_GAMES],
+}
+
+impl MatchState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one turn of input: the score lines, and the running mini-games,
+    /// a mini-game missing from them showing GAME_OVER.
+    pub fn update(&mut self, scores: &[PlayerScore], games: &[(usize, RunningGame)]) {
+        for idx in 0..NB_MINI_GAMES {
+            let state = games.iter().find(|(game, _)| *game == idx);
+            let open = self.runs[idx].last_mut().filter(|run| !run.is_finished());
+            match (state, open) {
+                (Some((_, state)), Some(run)) => run.states.push(state.clone()),
+                (Some((_, state)), None) => {
+                    let mut run = GameRun::new(idx, self.turn);
+                    run.states.push(state.clone());
+                    self.runs[idx].push(run);
+                }
+                (None, Some(run)) => {
+                    run.finished = true;
+                    run.medals = medals_won(&self.scores, scores, idx);
+                }
+                (None, None) => {}
+            }
+        }
+        self.scores = scores.to_vec();
+        self.turn += 1;
+    }
+
+    /// False until the first turn is recorded.
+    pub fn is_started(&self) -> bool {
+        self.turn > 0
+    }
+
+    /// Index of the current turn in the match, 0 on the first one.
+    pub fn turn(&self) -> usize {
+        self.turn.saturating_sub(1)
+    }
+
+    /// Turns left in the match, the current one included.
+    pub fn turns_left(&self) -> usize {
+        MAX_TURNS.saturating_sub(self.turn())
+    }
+
+    pub fn scores(&self) -> &[PlayerScore] {
+        &self.scores
+    }
+
+    /// Every run of the mini-game so far, the current one last.
+    pub fn runs(&self, game: usize) -> &[GameRun] {
+        &self.runs[game]
+    }
+
+    /// Run in progress, None during GAME_OVER.
+    pub fn current_run(&self, game: usize) -> Option<&GameRun> {
+        self.runs[game].last().filter(|run| !run.is_finished())
+    }
+
+    /// Placements of every finished run of the mini-game.
+    pub fn medals(&self, game: usize) -> Vec<[usize; 3]> {
+        self.runs[game]
+            .iter()
+            .filter_map(|run| run.medals)
+            .collect()
+    }
+
+    /// Mini-games as observed on the current turn.
+    pub fn last_games(&self) -> Vec<(usize, RunningGame)> {
+        (0..NB_MINI_GAMES)
+            .filter_map(|idx| {
+                let state = self.current_run(idx)?.last_state()?;
+                Some((idx, state.clone()))
+            })
+            .collect()
+    }
+}
+
+// placements from the medals gained in this mini-game between two score lines
+fn medals_won(before: &[PlayerScore], after: &[PlayerScore], game: usize) -> Option<[usize; 3]> {
+    if before.len() != 3 || after.len() != 3 {
+        return None;
+    }
+    let mut placements = [0; 3];
+    for (placement, (old, new)) in placements.iter_mut().zip(before.iter().zip(after)) {
+        let (old, new) = (old.games[game], new.games[game]);
+        *placement = if new.gold > old.gold {
+            0
+        } else if new.silver > old.silver {
+            1
+        } else if new.bronze > old.bronze {
+            2
+        } else {
+            return None;
+        };
+    }
+    Some(placements)
+}
+
+#[cfg(test)]
+mod test_match_state {
+    use super::*;
+    use crate::{action::Action::*, diving::Diving, mini_game::MiniGame};
+
+    fn diving(goals: &str) -> (usize, RunningGame) {
+        (3, RunningGame::Diving(Diving::new(goals, [0; 3], [0; 3])))
+    }
+
+    #[test]
+    fn tracks_runs_and_medals() {
+        let mut state = MatchState::new();
+        let scores = vec![PlayerScore::default(); 3];
+        let (idx, game) = diving("UD");
+
+        assert!(!state.is_started());
+        state.update(&scores, &[(idx, game.clone())]);
+        assert!(state.is_started());
+        let next = game.play([Up, Up, Left]);
+        state.update(&scores, &[(idx, next.clone())]);
+        assert_eq!(state.turn(), 1);
+        assert_eq!(state.turns_left(), MAX_TURNS - 1);
+        assert_eq!(state.current_run(idx).unwrap().turn(), 1);
+        assert_eq!(state.last_games(), vec![(idx, next)]);
+
+        // GAME_OVER, with the medals in the score lines
+        let mut medalled = scores.clone();
+        for (score, placement) in medalled.iter_mut().zip([1, 0, 2]) {
+            score.add_medal(idx, placement);
+        }
+        state.update(&medalled, &[]);
+        assert_eq!(state.current_run(idx), None);
+        assert!(state.last_games().is_empty());
+        assert_eq!(state.medals(idx), vec![[1, 0, 2]]);
+
+        state.update(&medalled, &[diving("LRL")]);
+        assert_eq!(state.runs(idx).len(), 2);
+        assert_eq!(state.current_run(idx).unwrap().start_turn, 3);
+        assert_eq!(state.current_run(idx).unwrap().turn(), 0);
+        assert!(state.runs(0).is_empty());
+    }
+
+    #[test]
+    fn shared_medals() {
+        let before = vec![PlayerScore::default(); 3];
+        let mut after = before.clone();
+        for (score, placement) in after.iter_mut().zip([0, 0, 2]) {
+            score.add_medal(1, placement);
+        }
+        assert_eq!(medals_won(&before, &after, 1), Some([0, 0, 2]));
+        assert_eq!(medals_won(&before, &after, 2), None);
+    }
+}
@@ -0,0 +1,353 @@
+// Simultaneous-move Monte Carlo tree search (decoupled UCT):
+// - a node is the state reached by a sequence of joint actions of the three players
+// - every node keeps separate action statistics for each player, and each player
+//   picks its own action by UCB1 on them, ignoring the others' choices
+// - a new node is scored by a short random rollout, drawn from the opponent model
+//   when one is given, every player getting its own
+//   medal-weighted points, normalized to [0, 1]
+// - neither the tree nor a rollout goes past the end of the match, where a mini-game
+//   still running hands out nothing
+// - my action is the most visited one at the root
+// - the subtree under the joint action played is the next turn's root, so the tree grown
+//   in the first turn's longer allowance keeps paying off
+
+use crate::{
+    action::Action,
+    medal_value::MedalWeights,
+    mini_game::{MiniGame, RunningGame},
+    opponent_model::{sample, Distribution, UNIFORM},
+    protocol::MAX_TURNS,
+    scoring::placement_points,
+    timer::Budget,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{collections::HashMap, mem};
+
+const EXPLORATION: f64 = 0.7;
+const ROLLOUT_TURNS: usize = 10;
+const GOLD_POINTS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ActionStats {
+    pub visits: u32,
+    pub total: f64,
+}
+
+impl ActionStats {
+    pub fn mean(&self) -> f64 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.total / self.visits as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    visits: u32,
+    // by player, then action in `Action::ALL` order
+    stats: [[ActionStats; 4]; 3],
+    children: HashMap<[Action; 3], usize>,
+}
+
+impl Node {
+    // UCB1 over the player's own statistics, unvisited actions first
+    fn select(&self, player: usize) -> Action {
+        let log_visits = (self.visits.max(1) as f64).ln();
+        let score = |stats: &ActionStats| {
+            if stats.visits == 0 {
+                f64::INFINITY
+            } else {
+                stats.mean() + EXPLORATION * (log_visits / stats.visits as f64).sqrt()
+            }
+        };
+        let mut best = 0;
+        for idx in 1..Action::ALL.len() {
+            if score(&self.stats[player][idx]) > score(&self.stats[player][best]) {
+                best = idx;
+            }
+        }
+        Action::ALL[best]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Mcts {
+    rng: StdRng,
+    max_iterations: u32,
+    policies: [Distribution; 3],
+    // turns left in the match, the current one included
+    turns_left: usize,
+    // arena, the root first
+    nodes: Vec<Node>,
+}
+
+impl Mcts {
+    pub fn new(seed: u64) -> Self {
+        Mcts {
+            rng: StdRng::seed_from_u64(seed),
+            max_iterations: u32::MAX,
+            policies: [UNIFORM; 3],
+            turns_left: MAX_TURNS,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Adds at most this many nodes to the tree in a search, one per iteration.
+    pub fn with_max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Distributions the rollout actions of each player are drawn from.
+    pub fn set_policies(&mut self, policies: [Distribution; 3]) {
+        self.policies = policies;
+    }
+
+    /// Turns left in the match, the current one included: the depth of the tree and its rollouts.
+    pub fn set_turns_left(&mut self, turns_left: usize) {
+        self.turns_left = turns_left;
+    }
+
+    /// Statistics of `player`'s actions at the root of the last search.
+    pub fn root_stats(&self, player: usize) -> [ActionStats; 4] {
+        self.nodes
+            .first()
+            .map_or([ActionStats::default(); 4], |root| root.stats[player])
+    }
+
+    /// Makes the child reached by the joint action played the root of the next search,
+    /// a fresh tree when the action is unknown or was never tried.
+    pub fn advance(&mut self, played: Option<[Action; 3]>) {
+        let root = self.nodes.first();
+        let child = played.and_then(|joint| root?.children.get(&joint).copied());
+        let Some(child) = child else {
+            self.nodes.clear();
+            return;
+        };
+        // moves the subtree to a new arena, parents before their children
+        let mut nodes = vec![mem::take(&mut self.nodes[child])];
+        let mut next = 0;
+        while next < nodes.len() {
+            let mut children = mem::take(&mut nodes[next].children);
+            for idx in children.values_mut() {
+                nodes.push(mem::take(&mut self.nodes[*idx]));
+                *idx = nodes.len() - 1;
+            }
+            nodes[next].children = children;
+            next += 1;
+        }
+        self.nodes = nodes;
+    }
+
+    /// Searches until the budget runs out, at least one iteration, and returns my most
+    /// visited action. The tree kept by `advance` is grown further.
+    /// `weights` holds the medal weights of every player.
+    pub fn search(
+        &mut self,
+        games: &[(usize, RunningGame)],
+        weights: &[MedalWeights; 3],
+        me: usize,
+        budget: &Budget,
+    ) -> Action {
+        if self.nodes.is_empty() {
+            self.nodes.push(Node::default());
+        }
+        let mut iterations = 0;
+        while iterations < self.max_iterations {
+            self.iterate(games, weights);
+            iterations += 1;
+            if budget.is_over() {
+                break;
+            }
+        }
+
+        let stats = self.root_stats(me);
+        bot_log::debug!("{iterations} iterations, {} nodes", self.nodes.len());
+        bot_log::trace!("root stats {stats:?}");
+        let mut best = 0;
+        for idx in 1..Action::ALL.len() {
+            if stats[idx].visits > stats[best].visits {
+                best = idx;
+            }
+        }
+        Action::ALL[best]
+    }
+
+    // selection down to a new node, rollout, then backpropagation along the path
+    fn iterate(&mut self, games: &[(usize, RunningGame)], weights: &[MedalWeights; 3]) {
+        let mut state: Vec<(usize, RunningGame)> = games.to_vec();
+        let mut path = Vec::new();
+        let mut node = 0;
+        while !is_over(&state) && path.len() < self.turns_left {
+            let joint = [0, 1, 2].map(|player| self.nodes[node].select(player));
+            play(&mut state, joint);
+            path.push((node, joint));
+
+            match self.nodes[node].children.get(&joint) {
+                Some(&child) => node = child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(joint, child);
+                    break;
+                }
+            }
+        }
+
+        let mut turns = path.len();
+        while turns < self.turns_left && turns < path.len() + ROLLOUT_TURNS {
+            if is_over(&state) {
+                break;
+            }
+            let joint = self.policies.map(|policy| sample(&policy, &mut self.rng));
+            play(&mut state, joint);
+            turns += 1;
+        }
+
+        let rewards = rewards(&state, weights, turns >= self.turns_left);
+        for (node, joint) in path {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            for (player, action) in joint.iter().enumerate() {
+                let stats = &mut node.stats[player][action.index()];
+                stats.visits += 1;
+                stats.total += rewards[player];
+            }
+        }
+    }
+}
+
+fn is_over(state: &[(usize, RunningGame)]) -> bool {
+    state.iter().all(|(_, game)| game.is_over())
+}
+
+fn play(state: &mut [(usize, RunningGame)], joint: [Action; 3]) {
+    for (_, game) in state.iter_mut().filter(|(_, game)| !game.is_over()) {
+        *game = game.play(joint);
+    }
+}
+
+// medal-weighted points of every player, over the most it could get,
+// only the finished mini-games counting once the match is over
+fn rewards(
+    state: &[(usize, RunningGame)],
+    weights: &[MedalWeights; 3],
+    match_over: bool,
+) -> [f64; 3] {
+    [0, 1, 2].map(|player| {
+        let mut points = 0;
+        let mut most = 0;
+        for (idx, game) in state {
+            let weight = weights[player].weight(*idx);
+            if game.is_over() || !match_over {
+                points += placement_points(game.standing()[player]) * weight;
+            }
+            most += GOLD_POINTS * weight;
+        }
+        if most == 0 {
+            0.0
+        } else {
+            points as f64 / most as f64
+        }
+    })
+}
+
+#[cfg(test)]
+mod test_mcts {
+    use super::*;
+    use crate::{action::Action::*, archery::Archery, diving::Diving, hurdles::HurdleRace};
+
+    fn mcts() -> Mcts {
+        Mcts::new(5).with_max_iterations(2000)
+    }
+
+    fn uniform() -> [MedalWeights; 3] {
+        [MedalWeights::uniform(); 3]
+    }
+
+    #[test]
+    fn every_player_finds_its_best_action() {
+        // archery rewards LEFT for player 0, RIGHT for player 1 and UP for player 2
+        let archery = Archery::new("5", [(5, 0), (-5, 0), (0, 5)]);
+        let games = [(1, RunningGame::Archery(archery))];
+        let mut mcts = mcts();
+
+        assert_eq!(
+            mcts.search(&games, &uniform(), 0, &Budget::unlimited()),
+            Left
+        );
+        let [_, _, _, right] = mcts.root_stats(1);
+        let [up, _, _, _] = mcts.root_stats(2);
+        assert!(right.visits > 1000 && up.visits > 1000);
+        assert_eq!(right.mean(), 1.0);
+    }
+
+    #[test]
+    fn race_decided_by_opponent_moves() {
+        // only UP clears the hurdle, and keeps me in the race against player 1
+        let race = HurdleRace::new("........#....#.......#........", [7, 9, 0], [0; 3]);
+        let games = [(0, RunningGame::Hurdles(race))];
+        let mut mcts = mcts();
+
+        assert_eq!(mcts.search(&games, &uniform(), 0, &Budget::unlimited()), Up);
+        let [up, _, down, _] = mcts.root_stats(0);
+        assert!(up.mean() > down.mean());
+    }
+
+    #[test]
+    fn keeps_the_tree_of_the_played_actions() {
+        let race = HurdleRace::new("........#....#.......#........", [7, 9, 0], [0; 3]);
+        let games = [(0, RunningGame::Hurdles(race))];
+        let mut mcts = mcts();
+        mcts.search(&games, &uniform(), 0, &Budget::unlimited());
+        let nodes = mcts.nodes.len();
+
+        let root = &mcts.nodes[0];
+        let (&played, &child) = root
+            .children
+            .iter()
+            .max_by_key(|(_, &child)| mcts.nodes[child].visits)
+            .unwrap();
+        let kept = mcts.nodes[child].stats;
+        mcts.advance(Some(played));
+        assert_eq!([0, 1, 2].map(|player| mcts.root_stats(player)), kept);
+        assert!(mcts.nodes.len() < nodes);
+        assert!(mcts.nodes[0]
+            .children
+            .values()
+            .all(|&idx| idx < mcts.nodes.len()));
+
+        mcts.advance(None);
+        assert_eq!(mcts.root_stats(0), [ActionStats::default(); 4]);
+    }
+
+    #[test]
+    fn no_medals_after_the_match() {
+        // diving ends after three turns, UP scoring for everyone
+        let diving = Diving::new("UUU", [0; 3], [0; 3]);
+        let games = [(3, RunningGame::Diving(diving))];
+        let mut mcts = mcts();
+
+        mcts.set_turns_left(2);
+        mcts.search(&games, &uniform(), 0, &Budget::unlimited());
+        assert!(mcts.root_stats(0).iter().all(|stats| stats.total == 0.0));
+
+        mcts.advance(None);
+        mcts.set_turns_left(3);
+        assert_eq!(mcts.search(&games, &uniform(), 0, &Budget::unlimited()), Up);
+        assert!(mcts.root_stats(0)[Up.index()].mean() > 0.0);
+    }
+
+    #[test]
+    fn stats_need_running_games() {
+        let archery = Archery::new("", [(0, 0); 3]);
+        let games = [(1, RunningGame::Archery(archery))];
+        let mut mcts = mcts();
+
+        assert_eq!(mcts.search(&games, &uniform(), 0, &Budget::unlimited()), Up);
+        assert_eq!(mcts.root_stats(0), [ActionStats::default(); 4]);
+        assert_eq!(rewards(&games, &uniform(), true), [1.0; 3]);
+    }
+}
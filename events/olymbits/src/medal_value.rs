@@ -0,0 +1,102 @@
+// Marginal value of a medal in each mini-game:
+// - climbing one placement is worth my expected final-score gain,
+//   plus the loss of the opponent I push down
+// - the final score being a product, the weakest mini-game weighs the most
+// - a mini-game without points would zero every gain, so each one counts
+//   one extra point
+
+use crate::scoring::{PlayerScore, NB_MINI_GAMES};
+
+// sum of the weights, so they read as per mille
+pub const TOTAL_WEIGHT: u32 = 1000;
+pub const UNIFORM_WEIGHT: u32 = TOTAL_WEIGHT / NB_MINI_GAMES as u32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MedalWeights([u32; NB_MINI_GAMES]);
+
+impl Default for MedalWeights {
+    fn default() -> Self {
+        MedalWeights::uniform()
+    }
+}
+
+impl MedalWeights {
+    pub fn uniform() -> Self {
+        MedalWeights([UNIFORM_WEIGHT; NB_MINI_GAMES])
+    }
+
+    /// Weights for `me` from the score breakdown of all three players.
+    pub fn new(scores: &[PlayerScore], me: usize) -> Self {
+        let mut gains = [0.0; NB_MINI_GAMES];
+        for (game, gain) in gains.iter_mut().enumerate() {
+            let opponents = scores.iter().enumerate().filter(|&(id, _)| id != me);
+            let opponents_loss = opponents
+                .map(|(_, score)| step_gain(score, game))
+                .sum::<f64>()
+                / (scores.len().max(2) - 1) as f64;
+            *gain = scores.get(me).map_or(0.0, |score| step_gain(score, game)) + opponents_loss;
+        }
+
+        let total: f64 = gains.iter().sum();
+        if total <= 0.0 {
+            return MedalWeights::uniform();
+        }
+        MedalWeights(gains.map(|gain| (gain / total * TOTAL_WEIGHT as f64).round() as u32))
+    }
+
+    /// Weight of the mini-game at this index in the score line.
+    pub fn weight(&self, game: usize) -> u32 {
+        self.0.get(game).copied().unwrap_or(UNIFORM_WEIGHT)
+    }
+
+    pub fn weights(&self) -> [u32; NB_MINI_GAMES] {
+        self.0
+    }
+}
+
+// final-score gain of one more point in `game`: the points of a placement being the same
+// in every mini-game, they scale all the gains alike and the normalization drops them
+fn step_gain(score: &PlayerScore, game: usize) -> f64 {
+    let smoothed = score.games.map(|g| g.points() as f64 + 1.0);
+    let product: f64 = smoothed.iter().product();
+    product / smoothed[game]
+}
+
+#[cfg(test)]
+mod test_medal_value {
+    use super::*;
+    use crate::scoring::MiniGameScore;
+
+    fn score(points: [u32; NB_MINI_GAMES]) -> PlayerScore {
+        // gold for each 3 points, silver for the rest
+        PlayerScore::new(points.map(|p| MiniGameScore::new(p / 3, p % 3, 0)))
+    }
+
+    #[test]
+    fn uniform_without_medals() {
+        let scores = vec![PlayerScore::default(); 3];
+        assert_eq!(MedalWeights::new(&scores, 0), MedalWeights::uniform());
+        assert_eq!(MedalWeights::new(&[], 0), MedalWeights::uniform());
+    }
+
+    #[test]
+    fn weakest_game_weighs_most() {
+        let even = score([3, 3, 3, 3]);
+        let scores = [score([9, 0, 9, 9]), even.clone(), even];
+        let weights = MedalWeights::new(&scores, 0);
+
+        assert!(weights.weight(1) > 2 * weights.weight(0));
+        assert_eq!(weights.weight(0), weights.weight(2));
+    }
+
+    #[test]
+    fn opponents_weakness_counts() {
+        let mine = score([3, 3, 3, 3]);
+        let scores = [mine.clone(), score([6, 6, 6, 0]), mine];
+        let weights = MedalWeights::new(&scores, 0);
+
+        // pushing the leader down in its weakest game is worth more
+        assert!(weights.weight(3) > weights.weight(0));
+        assert_eq!(weights.weight(7), UNIFORM_WEIGHT);
+    }
+}
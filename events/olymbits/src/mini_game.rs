@@ -0,0 +1,194 @@
+use crate::{
+    action::Action, archery::Archery, diving::Diving, hurdles::HurdleRace, roller::Roller,
+};
+
+// common surface of the four mini-games, so a search can run them side by side
+pub trait MiniGame: Sized {
+    /// Reads the game from its GPU and registers, None if they belong to another mini-game.
+    fn parse(gpu: &str, regs: [i32; 7]) -> Option<Self>;
+
+    /// Next state once every player played its action.
+    fn play(&self, actions: [Action; 3]) -> Self;
+
+    /// Next state if only `player` acts, the others standing still.
+    fn after_move(&self, player: usize, action: Action) -> Self;
+
+    fn is_over(&self) -> bool;
+
+    /// Placement of each player, 0 being gold. Tied players share the placement.
+    fn ranking(&self) -> [usize; 3];
+
+    /// How well `player` is doing, higher is better.
+    /// Only comparable between states of the same game.
+    fn progress(&self, player: usize) -> i32;
+
+    /// Placement of each player if the game stopped now: the ranking once over, the progress before.
+    fn standing(&self) -> [usize; 3] {
+        if self.is_over() {
+            self.ranking()
+        } else {
+            rank_by([0, 1, 2].map(|player| self.progress(player)))
+        }
+    }
+
+    /// GPU as sent to the players.
+    fn gpu(&self) -> String;
+
+    /// The 7 registers as sent to the players.
+    fn registers(&self) -> [i32; 7];
+}
+
+/// Placement from per-player keys, higher keys placing better.
+pub fn rank_by(keys: [i32; 3]) -> [usize; 3] {
+    keys.map(|mine| keys.iter().filter(|&&other| other > mine).count())
+}
+
+/// Rank of each action in `Action::ALL` order, 0 being the best,
+/// summed over every game with the weight of the game.
+pub fn rank_actions<'a, G: MiniGame + 'a>(
+    weighted_games: impl IntoIterator<Item = (&'a G, u32)>,
+    player: usize,
+) -> [u32; 4] {
+    let mut ranks = [0; 4];
+    for (game, weight) in weighted_games {
+        let progress = Action::ALL.map(|action| game.after_move(player, action).progress(player));
+        for (rank, mine) in ranks.iter_mut().zip(progress) {
+            *rank += progress.iter().filter(|&&other| other > mine).count() as u32 * weight;
+        }
+    }
+    ranks
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunningGame {
+    Hurdles(HurdleRace),
+    Archery(Archery),
+    Roller(Roller),
+    Diving(Diving),
+}
+
+macro_rules! delegate {
+    ($self:ident, $game:ident => $body:expr) => {
+        match $self {
+            RunningGame::Hurdles($game) => $body,
+            RunningGame::Archery($game) => $body,
+            RunningGame::Roller($game) => $body,
+            RunningGame::Diving($game) => $body,
+        }
+    };
+}
+
+impl MiniGame for RunningGame {
+    fn parse(gpu: &str, regs: [i32; 7]) -> Option<Self> {
+        HurdleRace::parse(gpu, regs)
+            .map(RunningGame::Hurdles)
+            .or_else(|| Archery::parse(gpu, regs).map(RunningGame::Archery))
+            .or_else(|| Roller::parse(gpu, regs).map(RunningGame::Roller))
+            .or_else(|| Diving::parse(gpu, regs).map(RunningGame::Diving))
+    }
+
+    fn play(&self, actions: [Action; 3]) -> Self {
+        match self {
+            RunningGame::Hurdles(game) => RunningGame::Hurdles(game.play(actions)),
+            RunningGame::Archery(game) => RunningGame::Archery(game.play(actions)),
+            RunningGame::Roller(game) => RunningGame::Roller(game.play(actions)),
+            RunningGame::Diving(game) => RunningGame::Diving(game.play(actions)),
+        }
+    }
+
+    fn after_move(&self, player: usize, action: Action) -> Self {
+        match self {
+            RunningGame::Hurdles(game) => RunningGame::Hurdles(game.after_move(player, action)),
+            RunningGame::Archery(game) => RunningGame::Archery(game.after_move(player, action)),
+            RunningGame::Roller(game) => RunningGame::Roller(game.after_move(player, action)),
+            RunningGame::Diving(game) => RunningGame::Diving(game.after_move(player, action)),
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        delegate!(self, game => game.is_over())
+    }
+
+    fn ranking(&self) -> [usize; 3] {
+        delegate!(self, game => game.ranking())
+    }
+
+    fn progress(&self, player: usize) -> i32 {
+        delegate!(self, game => game.progress(player))
+    }
+
+    fn gpu(&self) -> String {
+        delegate!(self, game => game.gpu())
+    }
+
+    fn registers(&self) -> [i32; 7] {
+        delegate!(self, game => game.registers())
+    }
+}
+
+#[cfg(test)]
+mod test_mini_game {
+    use super::*;
+    use crate::action::Action::*;
+
+    const NO_REG: i32 = -1;
+
+    #[test]
+    fn parse_each_mini_game() {
+        let regs = [1, 2, 3, 0, 0, 0, NO_REG];
+        let hurdles = RunningGame::parse(".....#...#...#................", regs);
+        assert!(matches!(hurdles, Some(RunningGame::Hurdles(_))));
+
+        let archery = RunningGame::parse("9914113315261", regs);
+        assert!(matches!(archery, Some(RunningGame::Archery(_))));
+
+        let roller = RunningGame::parse("ULDR", [1, 2, 3, 0, 0, 0, 15]);
+        assert!(matches!(roller, Some(RunningGame::Roller(_))));
+
+        let diving = RunningGame::parse("UUDLRLD", regs);
+        assert!(matches!(diving, Some(RunningGame::Diving(_))));
+
+        assert_eq!(RunningGame::parse("GAME_OVER", regs), None);
+    }
+
+    #[test]
+    fn registers_round_trip() {
+        let lines = [
+            (".....#...#...#................", [4, 9, 5, 0, 2, 3, NO_REG]),
+            ("9914113315261", [3, -4, 0, 20, -20, 7, NO_REG]),
+            ("RDLU", [12, 7, 9, 1, -2, 4, 11]),
+            ("UUDLRLD", [10, 3, 0, 4, 0, 1, NO_REG]),
+        ];
+        for (gpu, regs) in lines {
+            let game = RunningGame::parse(gpu, regs).unwrap();
+            assert_eq!(game.gpu(), gpu);
+            assert_eq!(game.registers(), regs);
+        }
+    }
+
+    #[test]
+    fn play_until_over() {
+        let mut game = RunningGame::parse("DDD", [0, 0, 0, 0, 0, 0, NO_REG]).unwrap();
+        while !game.is_over() {
+            game = game.play([Down, Down, Up]);
+        }
+        assert_eq!(game.ranking(), [0, 0, 2]);
+        assert_eq!(game.standing(), game.ranking());
+    }
+
+    #[test]
+    fn rank_actions_across_games() {
+        let games = [
+            RunningGame::parse("..........", [0, 0, 0, 0, 0, 0, NO_REG]).unwrap(),
+            RunningGame::parse("LLL", [0, 0, 0, 0, 0, 0, NO_REG]).unwrap(),
+        ];
+
+        // hurdles ranks RIGHT, then UP and DOWN, then LEFT; diving wants LEFT
+        assert_eq!(rank_actions(games.iter().zip([1, 1]), 0), [2, 3, 2, 1]);
+        assert_eq!(rank_actions(games.iter().zip([1, 3]), 0), [4, 3, 4, 3]);
+        assert_eq!(rank_by([5, 9, 5]), [1, 0, 1]);
+        // RIGHT leads the race before it ends
+        let race = games[0].play([Left, Right, Down]);
+        assert_eq!(race.standing(), [2, 0, 1]);
+    }
+}
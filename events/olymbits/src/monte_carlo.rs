@@ -0,0 +1,258 @@
+// Monte Carlo action evaluation:
+// - every playout starts with one of my four actions, the opponents playing at random
+// - all running mini-games are then played out with random actions for everyone,
+//   drawn from the opponent model when one is given
+// - a playout is worth the medal points it ends with, weighted by the mini-game weights;
+//   a mini-game still running when the match ends hands out nothing
+// - actions are sampled in turn until the turn budget or the playout limit runs out
+
+use crate::{
+    action::Action,
+    medal_value::MedalWeights,
+    mini_game::{MiniGame, RunningGame},
+    opponent_model::{sample, Distribution, UNIFORM},
+    scoring::placement_points,
+    timer::Budget,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+// a playout never outlasts the match
+const MAX_PLAYOUT_TURNS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Evaluation {
+    /// Mean medal-weighted points of each action, in `Action::ALL` order.
+    pub values: [f64; 4],
+    pub playouts: [u32; 4],
+}
+
+impl Evaluation {
+    /// Highest mean value, the first action on ties.
+    pub fn best_action(&self) -> Action {
+        let mut best = 0;
+        for idx in 1..Action::ALL.len() {
+            if self.values[idx] > self.values[best] {
+                best = idx;
+            }
+        }
+        Action::ALL[best]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    rng: StdRng,
+    max_playouts: u32,
+    policies: [Distribution; 3],
+    turns_left: usize,
+}
+
+impl MonteCarlo {
+    pub fn new(seed: u64) -> Self {
+        MonteCarlo {
+            rng: StdRng::seed_from_u64(seed),
+            max_playouts: u32::MAX,
+            policies: [UNIFORM; 3],
+            turns_left: MAX_PLAYOUT_TURNS,
+        }
+    }
+
+    /// Stops sampling after this many playouts of each action, so an evaluation is repeatable.
+    pub fn with_max_playouts(mut self, max_playouts: u32) -> Self {
+        self.max_playouts = max_playouts;
+        self
+    }
+
+    /// Distributions the random actions of each player are drawn from.
+    pub fn set_policies(&mut self, policies: [Distribution; 3]) {
+        self.policies = policies;
+    }
+
+    /// Turns left in the match, the current one included.
+    pub fn set_turns_left(&mut self, turns_left: usize) {
+        self.turns_left = turns_left.min(MAX_PLAYOUT_TURNS);
+    }
+
+    /// Plays out every action until the budget runs out, at least once each.
+    pub fn evaluate(
+        &mut self,
+        games: &[(usize, RunningGame)],
+        weights: &MedalWeights,
+        me: usize,
+        budget: &Budget,
+    ) -> Evaluation {
+        let mut totals = [0.0; 4];
+        let mut playouts = [0; 4];
+        while playouts[0] < self.max_playouts {
+            for (idx, action) in Action::ALL.into_iter().enumerate() {
+                totals[idx] += self.playout(games, weights, me, action);
+                playouts[idx] += 1;
+            }
+            if budget.is_over() {
+                break;
+            }
+        }
+
+        let mut values = totals;
+        for (value, count) in values.iter_mut().zip(playouts) {
+            *value /= count as f64;
+        }
+        bot_log::debug!("values {values:.0?} after {} playouts", playouts[0]);
+        Evaluation { values, playouts }
+    }
+
+    pub fn best_action(
+        &mut self,
+        games: &[(usize, RunningGame)],
+        weights: &MedalWeights,
+        me: usize,
+        budget: &Budget,
+    ) -> Action {
+        self.evaluate(games, weights, me, budget).best_action()
+    }
+
+    // medal-weighted points of one random playout starting with `first`
+    fn playout(
+        &mut self,
+        games: &[(usize, RunningGame)],
+        weights: &MedalWeights,
+        me: usize,
+        first: Action,
+    ) -> f64 {
+        let mut states: Vec<(usize, RunningGame)> = games
+            .iter()
+            .filter(|(_, game)| !game.is_over())
+            .cloned()
+            .collect();
+
+        let mut actions = self.random_actions();
+        actions[me] = first;
+        for _ in 0..self.turns_left {
+            if states.iter().all(|(_, game)| game.is_over()) {
+                break;
+            }
+            for (_, game) in states.iter_mut().filter(|(_, game)| !game.is_over()) {
+                *game = game.play(actions);
+            }
+            actions = self.random_actions();
+        }
+
+        states
+            .iter()
+            .filter(|(_, game)| game.is_over())
+            .map(|(idx, game)| {
+                let placement = game.ranking()[me];
+                (placement_points(placement) * weights.weight(*idx)) as f64
+            })
+            .sum()
+    }
+
+    fn random_actions(&mut self) -> [Action; 3] {
+        self.policies.map(|policy| sample(&policy, &mut self.rng))
+    }
+}
+
+#[cfg(test)]
+mod test_monte_carlo {
+    use super::*;
+    use crate::{
+        action::Action::*,
+        archery::Archery,
+        diving::Diving,
+        hurdles::HurdleRace,
+        scoring::{MiniGameScore, PlayerScore},
+    };
+    use std::time::Duration;
+
+    fn monte_carlo() -> MonteCarlo {
+        MonteCarlo::new(7).with_max_playouts(200)
+    }
+
+    #[test]
+    fn avoids_hurdle() {
+        // only UP jumps the hurdle right ahead
+        let race = HurdleRace::new("....#.........", [3, 3, 3], [0; 3]);
+        let games = [(0, RunningGame::Hurdles(race))];
+        let evaluation =
+            monte_carlo().evaluate(&games, &MedalWeights::uniform(), 0, &Budget::unlimited());
+
+        assert_eq!(evaluation.playouts, [200; 4]);
+        assert!(evaluation.values[Up.index()] > evaluation.values[Right.index()]);
+        assert_eq!(evaluation.best_action(), Up);
+    }
+
+    // weights for player 0 with these golds in each mini-game
+    fn weights(golds: [u32; 4]) -> MedalWeights {
+        let mut scores = vec![PlayerScore::default(); 3];
+        scores[0] = PlayerScore::new(golds.map(|gold| MiniGameScore::new(gold, 0, 0)));
+        MedalWeights::new(&scores, 0)
+    }
+
+    #[test]
+    fn heavier_game_wins() {
+        // archery wants LEFT, diving wants UP, equally likely to win a medal
+        let archery = Archery::new("9", [(9, 0); 3]);
+        let diving = Diving::new("U", [0; 3], [0; 3]);
+        let games = [
+            (1, RunningGame::Archery(archery)),
+            (3, RunningGame::Diving(diving)),
+        ];
+
+        let archery_heavy = weights([3, 0, 3, 3]);
+        assert!(archery_heavy.weight(1) > archery_heavy.weight(3));
+        assert_eq!(
+            monte_carlo().best_action(&games, &archery_heavy, 0, &Budget::unlimited()),
+            Left
+        );
+
+        let diving_heavy = weights([3, 3, 3, 0]);
+        assert_eq!(
+            monte_carlo().best_action(&games, &diving_heavy, 0, &Budget::unlimited()),
+            Up
+        );
+    }
+
+    #[test]
+    fn expects_modeled_opponents() {
+        // opponent 1 surely reaches the target: LEFT ties it for gold, anything else is silver
+        let archery = Archery::new("5", [(5, 0), (-5, 0), (20, 20)]);
+        let games = [(1, RunningGame::Archery(archery))];
+        let mut monte_carlo = monte_carlo();
+        monte_carlo.set_policies([UNIFORM, [0.0, 0.0, 0.0, 1.0], UNIFORM]);
+
+        let evaluation =
+            monte_carlo.evaluate(&games, &MedalWeights::uniform(), 0, &Budget::unlimited());
+        assert_eq!(evaluation.values[Left.index()], 750.0);
+        assert_eq!(evaluation.values[Right.index()], 250.0);
+    }
+
+    #[test]
+    fn no_medals_after_the_match() {
+        let diving = Diving::new("UUU", [0; 3], [0; 3]);
+        let games = [(3, RunningGame::Diving(diving))];
+        let mut monte_carlo = monte_carlo();
+
+        monte_carlo.set_turns_left(2);
+        let evaluation =
+            monte_carlo.evaluate(&games, &MedalWeights::uniform(), 0, &Budget::unlimited());
+        assert_eq!(evaluation.values, [0.0; 4]);
+
+        monte_carlo.set_turns_left(3);
+        let evaluation =
+            monte_carlo.evaluate(&games, &MedalWeights::uniform(), 0, &Budget::unlimited());
+        assert_eq!(evaluation.best_action(), Up);
+    }
+
+    #[test]
+    fn stops_at_budget() {
+        let race = HurdleRace::new(&".".repeat(30), [0; 3], [0; 3]);
+        let games = [(0, RunningGame::Hurdles(race))];
+        let mut monte_carlo = MonteCarlo::new(1);
+        let budget = Budget::new(Duration::ZERO);
+        let evaluation = monte_carlo.evaluate(&games, &MedalWeights::uniform(), 2, &budget);
+
+        assert_eq!(evaluation.playouts, [1; 4]);
+        let nothing_running = monte_carlo.evaluate(&[], &MedalWeights::uniform(), 2, &budget);
+        assert_eq!(nothing_running.values, [0.0; 4]);
+    }
+}
@@ -0,0 +1,273 @@
+// Opponent model learned during the match:
+// - the actions of every player are inferred from the turn-to-turn diff: every joint action
+//   is replayed on last turn's mini-games, and kept if it gives this turn's registers
+// - a player with several consistent actions (stunned, say) shares the observation between them
+// - the next action follows the observed transitions from the last action (bigram),
+//   backed off to the overall action frequencies
+
+use crate::{
+    action::Action,
+    mini_game::{MiniGame, RunningGame},
+};
+use rand::{distributions::WeightedIndex, prelude::Distribution as _, Rng};
+
+/// Probability of each action, in `Action::ALL` order.
+pub type Distribution = [f64; 4];
+
+pub const UNIFORM: Distribution = [0.25; 4];
+
+/// Draws an action from the distribution, uniformly if it has no weight.
+pub fn sample(distribution: &Distribution, rng: &mut impl Rng) -> Action {
+    match WeightedIndex::new(distribution) {
+        Ok(index) => Action::ALL[index.sample(rng)],
+        Err(_) => Action::ALL[rng.gen_range(0..4)],
+    }
+}
+
+/// Actions of each player consistent with going from `previous` to `current`,
+/// every action when no mini-game tells them apart.
+pub fn infer_actions(
+    previous: &[(usize, RunningGame)],
+    current: &[(usize, RunningGame)],
+) -> [Vec<Action>; 3] {
+    let pairs: Vec<(&RunningGame, &RunningGame)> = previous
+        .iter()
+        .filter(|(_, game)| !game.is_over())
+        .filter_map(|(idx, before)| {
+            let (_, after) = current.iter().find(|(other, _)| other == idx)?;
+            // the GPU does not depend on the actions, only roller redraws it every turn
+            let same_run = match (before, after) {
+                (RunningGame::Roller(_), RunningGame::Roller(_)) => true,
+                _ => before.play([Action::Up; 3]).gpu() == after.gpu(),
+            };
+            same_run.then_some((before, after))
+        })
+        .collect();
+
+    let mut consistent = [[false; 4]; 3];
+    for first in Action::ALL {
+        for second in Action::ALL {
+            for third in Action::ALL {
+                let joint = [first, second, third];
+                let matches = pairs
+                    .iter()
+                    .all(|(before, after)| before.play(joint).registers() == after.registers());
+                if matches {
+                    for (player, action) in joint.iter().enumerate() {
+                        consistent[player][action.index()] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    consistent.map(|flags| {
+        let actions: Vec<Action> = Action::ALL
+            .into_iter()
+            .filter(|action| flags[action.index()])
+            .collect();
+        // no joint action explains the diff: the simulators missed a rule
+        if actions.is_empty() {
+            Action::ALL.to_vec()
+        } else {
+            actions
+        }
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PlayerHistory {
+    counts: [f64; 4],
+    // by previous action, then next action
+    transitions: [[f64; 4]; 4],
+    last: Option<Action>,
+}
+
+impl PlayerHistory {
+    fn record(&mut self, candidates: &[Action]) {
+        let share = 1.0 / candidates.len() as f64;
+        for action in candidates {
+            self.counts[action.index()] += share;
+            if let Some(last) = self.last {
+                self.transitions[last.index()][action.index()] += share;
+            }
+        }
+        // ambiguous turns break the chain
+        self.last = match candidates {
+            [action] => Some(*action),
+            _ => None,
+        };
+    }
+
+    // frequencies with one pseudo-count per action, then transitions with the frequencies as prior
+    fn distribution(&self) -> Distribution {
+        let total: f64 = self.counts.iter().sum();
+        let frequencies = [0, 1, 2, 3].map(|idx| (self.counts[idx] + 1.0) / (total + 4.0));
+        let Some(last) = self.last else {
+            return frequencies;
+        };
+        let row = self.transitions[last.index()];
+        let row_total: f64 = row.iter().sum();
+        [0, 1, 2, 3].map(|idx| (row[idx] + frequencies[idx]) / (row_total + 1.0))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OpponentModel {
+    previous: Vec<(usize, RunningGame)>,
+    players: [PlayerHistory; 3],
+    // joint action of the last turn, when the diff told every player's apart
+    last_actions: Option<[Action; 3]>,
+}
+
+impl OpponentModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the actions leading to this turn's mini-games, the first turn only remembers them.
+    pub fn observe(&mut self, games: &[(usize, RunningGame)]) {
+        self.last_actions = None;
+        if !self.previous.is_empty() {
+            let inferred = infer_actions(&self.previous, games);
+            bot_log::trace!("inferred actions {inferred:?}");
+            if let [[first], [second], [third]] = &inferred.each_ref().map(Vec::as_slice) {
+                self.last_actions = Some([*first, *second, *third]);
+            }
+            for (history, candidates) in self.players.iter_mut().zip(&inferred) {
+                // nothing learned from a turn without a running mini-game
+                if candidates.len() < Action::ALL.len() {
+                    history.record(candidates);
+                }
+            }
+        }
+        self.previous = games.to_vec();
+    }
+
+    /// Actions every player played on the last turn, None unless each was the only one
+    /// consistent with the diff.
+    pub fn last_actions(&self) -> Option<[Action; 3]> {
+        self.last_actions
+    }
+
+    /// Likelihood of each action of `player` on the coming turn.
+    pub fn distribution(&self, player: usize) -> Distribution {
+        self.players[player].distribution()
+    }
+
+    /// Distributions of every player, `me` playing uniformly.
+    pub fn distributions(&self, me: usize) -> [Distribution; 3] {
+        [0, 1, 2].map(|player| {
+            if player == me {
+                UNIFORM
+            } else {
+                self.distribution(player)
+            }
+        })
+    }
+
+    pub fn most_likely(&self, player: usize) -> Action {
+        let distribution = self.distribution(player);
+        let mut best = 0;
+        for idx in 1..Action::ALL.len() {
+            if distribution[idx] > distribution[best] {
+                best = idx;
+            }
+        }
+        Action::ALL[best]
+    }
+}
+
+#[cfg(test)]
+mod test_opponent_model {
+    use super::*;
+    use crate::{action::Action::*, diving::Diving, hurdles::HurdleRace, roller::Roller};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const TRACK: &str = "....................#.........";
+
+    fn race(positions: [i32; 3], stuns: [i32; 3]) -> Vec<(usize, RunningGame)> {
+        vec![(
+            0,
+            RunningGame::Hurdles(HurdleRace::new(TRACK, positions, stuns)),
+        )]
+    }
+
+    #[test]
+    fn infers_from_hurdle_diff() {
+        let before = race([0, 0, 0], [0, 0, 2]);
+        let after = race([3, 2, 0], [0, 0, 1]);
+        let [mine, second, stunned] = infer_actions(&before, &after);
+
+        assert_eq!(mine, vec![Right]);
+        // UP and DOWN both move 2 spaces without a hurdle around
+        assert_eq!(second, vec![Up, Down]);
+        assert_eq!(stunned, Action::ALL.to_vec());
+    }
+
+    #[test]
+    fn mini_games_narrow_down_actions() {
+        let diving = Diving::new("DU", [0; 3], [0; 3]);
+        let mut before = race([0, 0, 0], [0; 3]);
+        before.push((3, RunningGame::Diving(diving.clone())));
+        let mut after = race([3, 2, 2], [0; 3]);
+        after.push((3, RunningGame::Diving(diving.play([Right, Down, Up]))));
+
+        assert_eq!(
+            infer_actions(&before, &after),
+            [vec![Right], vec![Down], vec![Up]]
+        );
+        let mut model = OpponentModel::new();
+        model.observe(&before);
+        model.observe(&after);
+        assert_eq!(model.last_actions(), Some([Right, Down, Up]));
+        // a new run tells nothing
+        let restarted = vec![(3, RunningGame::Diving(Diving::new("LL", [0; 3], [0; 3])))];
+        assert_eq!(
+            infer_actions(&before[1..], &restarted)[0],
+            Action::ALL.to_vec()
+        );
+    }
+
+    #[test]
+    fn roller_collisions_need_joint_actions() {
+        // player 1 lands on player 0's space with DOWN, both got the collision risk
+        let roller = Roller::new("URDL", [2, 0, 5], [0; 3], 10);
+        let before = vec![(2, RunningGame::Roller(roller.clone()))];
+        let after = vec![(2, RunningGame::Roller(roller.play([Up, Down, Left])))];
+
+        assert_eq!(
+            infer_actions(&before, &after),
+            [vec![Up], vec![Down], vec![Left]]
+        );
+    }
+
+    #[test]
+    fn learns_fixed_policy() {
+        let mut model = OpponentModel::new();
+        let mut positions = [0, 0, 0];
+        model.observe(&race(positions, [0; 3]));
+        assert_eq!(model.distribution(1), UNIFORM);
+        assert_eq!(model.last_actions(), None);
+
+        // player 1 always goes LEFT, player 2 alternates RIGHT and LEFT
+        for turn in 0..4 {
+            positions[0] += 2;
+            positions[1] += 1;
+            positions[2] += if turn % 2 == 0 { 3 } else { 1 };
+            model.observe(&race(positions, [0; 3]));
+        }
+
+        assert_eq!(model.most_likely(1), Left);
+        assert!(model.distribution(1)[Left.index()] > 0.5);
+        // the last move was LEFT, RIGHT always followed it
+        assert_eq!(model.most_likely(2), Right);
+        assert_eq!(model.distributions(1)[1], UNIFORM);
+        // UP and DOWN both move player 0 by 2
+        assert_eq!(model.last_actions(), None);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let draws = (0..100).filter(|_| sample(&model.distribution(1), &mut rng) == Left);
+        assert!(draws.count() > 50);
+    }
+}
@@ -0,0 +1,127 @@
+// Olymbits input protocol:
+// - init: my player index, then the number of mini-games
+// - each turn: one score line per player, then one game line per mini-game
+// - a game line is the GPU then reg_0..reg_6, GAME_OVER between two runs
+
+use crate::{
+    mini_game::{MiniGame, RunningGame},
+    scoring::{MiniGameScore, PlayerScore, NB_MINI_GAMES},
+};
+use line_reader::{ErrorKind, LineReader, ParseError};
+use std::io::BufRead;
+
+pub const NB_PLAYERS: usize = 3;
+// turns of a match
+pub const MAX_TURNS: usize = 100;
+pub const NB_REGISTERS: usize = 7;
+const REGISTER_NAMES: [&str; NB_REGISTERS] = [
+    "reg_0", "reg_1", "reg_2", "reg_3", "reg_4", "reg_5", "reg_6",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Init {
+    pub player: usize,
+    pub nb_games: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameLine {
+    pub gpu: String,
+    pub regs: [i32; NB_REGISTERS],
+}
+
+impl GameLine {
+    /// The simulated mini-game, None during GAME_OVER.
+    pub fn game(&self) -> Option<RunningGame> {
+        RunningGame::parse(&self.gpu, self.regs)
+    }
+}
+
+pub fn read_init(reader: &mut LineReader<impl BufRead>) -> Result<Init, ParseError> {
+    let player: usize = reader.next_value("player index")?;
+    if player >= NB_PLAYERS {
+        let kind = ErrorKind::Invalid {
+            value: player.to_string(),
+            reason: format!("expected a player below {NB_PLAYERS}"),
+        };
+        return Err(ParseError::new(reader.line_number(), "player index", kind));
+    }
+    let nb_games = reader.next_value("number of mini-games")?;
+    Ok(Init { player, nb_games })
+}
+
+/// Reads "final_score gold silver bronze ..." with one medal triple per mini-game.
+pub fn read_score_line(reader: &mut LineReader<impl BufRead>) -> Result<PlayerScore, ParseError> {
+    let line = reader.next_line("score line")?;
+    let mut fields = line.fields();
+    let final_score = fields.next("final score")?;
+    let mut games = [MiniGameScore::default(); NB_MINI_GAMES];
+    for (idx, game) in games.iter_mut().enumerate() {
+        *game = MiniGameScore::new(
+            fields.next(&format!("gold of game {idx}"))?,
+            fields.next(&format!("silver of game {idx}"))?,
+            fields.next(&format!("bronze of game {idx}"))?,
+        );
+    }
+    fields.end()?;
+    Ok(PlayerScore { final_score, games })
+}
+
+pub fn read_game_line(reader: &mut LineReader<impl BufRead>) -> Result<GameLine, ParseError> {
+    let line = reader.next_line("game line")?;
+    let mut fields = line.fields();
+    let gpu = fields.next_str("gpu")?.to_string();
+    let mut regs = [0; NB_REGISTERS];
+    for (reg, name) in regs.iter_mut().zip(REGISTER_NAMES) {
+        *reg = fields.next(name)?;
+    }
+    fields.end()?;
+    Ok(GameLine { gpu, regs })
+}
+
+#[cfg(test)]
+mod test_protocol {
+    use super::*;
+
+    fn reader(input: &str) -> LineReader<&[u8]> {
+        LineReader::new(input.as_bytes())
+    }
+
+    #[test]
+    fn reads_a_turn() {
+        let mut input = reader(
+            "1\n4\n40 2 1 0 1 0 3 0 1 2 1 1 1 \n\
+             .....#...#...#................ 3 0 0 0 2 0 -1\n\
+             GAME_OVER -1 -1 -1 -1 -1 -1 -1\n",
+        );
+        assert_eq!(
+            read_init(&mut input),
+            Ok(Init {
+                player: 1,
+                nb_games: 4
+            })
+        );
+        assert_eq!(read_score_line(&mut input).unwrap().final_score, 40);
+
+        let race = read_game_line(&mut input).unwrap();
+        assert_eq!(race.regs, [3, 0, 0, 0, 2, 0, -1]);
+        assert!(matches!(race.game(), Some(RunningGame::Hurdles(_))));
+        assert_eq!(read_game_line(&mut input).unwrap().game(), None);
+        assert!(read_game_line(&mut input).unwrap_err().is_end_of_input());
+    }
+
+    #[test]
+    fn reports_broken_lines() {
+        let err = read_init(&mut reader("3\n4\n")).unwrap_err();
+        assert_eq!(err.line, 1);
+        assert_eq!(err.field, "player index");
+
+        let err = read_score_line(&mut reader("0 1 2 3 1 2 3 1 2 3 1 2\n")).unwrap_err();
+        assert_eq!(err.to_string(), "line 1, bronze of game 3: missing");
+
+        let err = read_game_line(&mut reader("GAME_OVER -1 -1 x -1 -1 -1 -1\n")).unwrap_err();
+        assert_eq!(err.field, "reg_2");
+        let err = read_game_line(&mut reader("9914 0 0 0 0 0 0 -1 7\n")).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::ExtraField("7".to_string()));
+    }
+}
@@ -0,0 +1,168 @@
+// Local referee, following the game rules:
+// - the four mini-games run side by side, every player action applies to all of them
+// - a finished mini-game hands out its medals, shows GAME_OVER for one turn, then restarts
+// - the match lasts 100 turns, the final score is the product of the mini-game points
+
+use crate::{
+    action::Action,
+    generator::Generator,
+    mini_game::{MiniGame, RunningGame},
+    protocol::MAX_TURNS,
+    scoring::{PlayerScore, NB_MINI_GAMES},
+};
+
+pub const GAME_OVER: &str = "GAME_OVER";
+
+const NO_REGS: [i32; 7] = [-1; 7];
+
+#[derive(Debug, Clone)]
+pub struct Referee {
+    generator: Generator,
+    // None while the mini-game shows GAME_OVER
+    games: [Option<RunningGame>; NB_MINI_GAMES],
+    scores: [PlayerScore; 3],
+    turn: usize,
+}
+
+impl Referee {
+    pub fn new(mut generator: Generator) -> Self {
+        let games = [0, 1, 2, 3].map(|game| Some(generator.game(game)));
+        Referee {
+            generator,
+            games,
+            scores: Default::default(),
+            turn: 0,
+        }
+    }
+
+    /// Initialization input of `player`: its index and the number of mini-games.
+    pub fn init_lines(&self, player: usize) -> Vec<String> {
+        vec![player.to_string(), NB_MINI_GAMES.to_string()]
+    }
+
+    /// Turn input, the same for every player: the 3 score lines, then one line per mini-game.
+    pub fn turn_lines(&self) -> Vec<String> {
+        let scores = self.scores.iter().map(PlayerScore::to_string);
+        let games = self.games.iter().map(|game| match game {
+            Some(game) => {
+                let regs = game.registers().map(|reg| reg.to_string());
+                format!("{} {}", game.gpu(), regs.join(" "))
+            }
+            None => {
+                let regs = NO_REGS.map(|reg| reg.to_string());
+                format!("{} {}", GAME_OVER, regs.join(" "))
+            }
+        });
+        scores.chain(games).collect()
+    }
+
+    /// Applies the actions to every mini-game and hands out the medals of the finished ones.
+    pub fn play_turn(&mut self, actions: [Action; 3]) {
+        for idx in 0..NB_MINI_GAMES {
+            let Some(game) = &self.games[idx] else {
+                self.games[idx] = Some(self.generator.game(idx));
+                continue;
+            };
+
+            let mut next = game.play(actions);
+            if next.is_over() {
+                for (score, placement) in self.scores.iter_mut().zip(next.ranking()) {
+                    score.add_medal(idx, placement);
+                }
+                self.games[idx] = None;
+                continue;
+            }
+            if let RunningGame::Roller(roller) = &mut next {
+                roller.set_risk_order(&self.generator.risk_order());
+            }
+            self.games[idx] = Some(next);
+        }
+        self.turn += 1;
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.turn >= MAX_TURNS
+    }
+
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn games(&self) -> &[Option<RunningGame>; NB_MINI_GAMES] {
+        &self.games
+    }
+
+    pub fn scores(&self) -> &[PlayerScore; 3] {
+        &self.scores
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.generator.seed()
+    }
+}
+
+#[cfg(test)]
+mod test_referee {
+    use super::*;
+    use crate::action::Action::*;
+
+    #[test]
+    fn turn_lines_parse_back() {
+        let referee = Referee::new(Generator::new(7));
+        let lines = referee.turn_lines();
+        assert_eq!(lines.len(), 3 + NB_MINI_GAMES);
+        assert_eq!(lines[0], "0 0 0 0 0 0 0 0 0 0 0 0 0");
+
+        for (line, game) in lines[3..].iter().zip(referee.games()) {
+            let mut fields = line.split_whitespace();
+            let gpu = fields.next().unwrap();
+            let regs: Vec<i32> = fields.map(|reg| reg.parse().unwrap()).collect();
+            let parsed = RunningGame::parse(gpu, regs.try_into().unwrap());
+            assert_eq!(parsed.as_ref(), game.as_ref());
+        }
+    }
+
+    #[test]
+    fn finished_game_shows_game_over_then_restarts() {
+        let mut referee = Referee::new(Generator::new(7));
+        while referee.games()[0].is_some() {
+            referee.play_turn([Right, Up, Left]);
+        }
+        assert!(referee.turn_lines()[3].starts_with(GAME_OVER));
+        let medals = referee.scores().iter().map(|score| score.games[0]);
+        let handed_out: u32 = medals
+            .map(|game| game.gold + game.silver + game.bronze)
+            .sum();
+        assert_eq!(handed_out, 3);
+
+        referee.play_turn([Right, Up, Left]);
+        assert!(matches!(referee.games()[0], Some(RunningGame::Hurdles(_))));
+    }
+
+    #[test]
+    fn match_lasts_max_turns() {
+        let mut referee = Referee::new(Generator::new(3));
+        while !referee.is_over() {
+            referee.play_turn([Up, Down, Right]);
+        }
+        assert_eq!(referee.turn(), MAX_TURNS);
+
+        let medals = referee.scores().iter().map(|score| score.games[3]);
+        let runs: u32 = medals
+            .map(|game| game.gold + game.silver + game.bronze)
+            .sum();
+        assert!(runs >= 3 * 5);
+    }
+
+    #[test]
+    fn same_seed_same_match() {
+        let mut first = Referee::new(Generator::new(9));
+        let mut second = Referee::new(Generator::new(9));
+        while !first.is_over() {
+            assert_eq!(first.turn_lines(), second.turn_lines());
+            first.play_turn([Right, Down, Up]);
+            second.play_turn([Right, Down, Up]);
+        }
+        assert_eq!(first.scores(), second.scores());
+    }
+}
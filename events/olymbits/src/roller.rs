@@ -0,0 +1,222 @@
+// Roller speed skating simulator, following the referee rules:
+// - the GPU is this turn's risk order, safest action first
+// - actions move 1, 2, 2, 3 spaces and change risk by -1, 0, +1, +2 in that order
+// - landing on the same space as another player on the 10-space loop adds 2 risk
+// - reaching risk 5 stuns the player for the next 2 turns and resets its risk
+// - stunned players have their stun timer as a negative risk
+// - the game ends when no turns are left, most spaces travelled wins
+
+use crate::{
+    action::Action,
+    mini_game::{rank_by, MiniGame},
+};
+
+pub const LOOP_SPACES: i32 = 10;
+pub const MAX_RISK: i32 = 5;
+pub const STUN_TURNS: i32 = 2;
+const COLLISION_RISK: i32 = 2;
+// (spaces, risk change) by index in the risk order
+const STRIDES: [(i32, i32); 4] = [(1, -1), (2, 0), (2, 1), (3, 2)];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Roller {
+    risk_order: String,
+    spaces: [i32; 3],
+    risks: [i32; 3],
+    turns_left: i32,
+}
+
+impl Roller {
+    pub fn new(risk_order: &str, spaces: [i32; 3], risks: [i32; 3], turns_left: i32) -> Self {
+        Roller {
+            risk_order: risk_order.to_string(),
+            spaces,
+            risks,
+            turns_left,
+        }
+    }
+
+    /// Roller GPU is a permutation of "UDLR", and reg_6 counts the turns left.
+    pub fn matches(gpu: &str, regs: [i32; 7]) -> bool {
+        gpu.len() == 4 && "UDLR".chars().all(|c| gpu.contains(c)) && regs[6] >= 0
+    }
+
+    pub fn spaces(&self) -> [i32; 3] {
+        self.spaces
+    }
+
+    pub fn risks(&self) -> [i32; 3] {
+        self.risks
+    }
+
+    /// The referee draws a new risk order every turn.
+    pub fn set_risk_order(&mut self, risk_order: &str) {
+        self.risk_order = risk_order.to_string();
+    }
+
+    pub fn turns_left(&self) -> i32 {
+        self.turns_left
+    }
+
+    pub fn is_stunned(&self, player: usize) -> bool {
+        self.risks[player] < 0
+    }
+
+    fn stride(&self, action: Action) -> (i32, i32) {
+        self.risk_order
+            .chars()
+            .position(|c| c == action.initial())
+            .map_or((0, 0), |idx| STRIDES[idx])
+    }
+
+    // moves the player, returns false if it was stunned
+    fn move_player(&mut self, player: usize, action: Action) -> bool {
+        if self.is_stunned(player) {
+            self.risks[player] += 1;
+            return false;
+        }
+        let (spaces, risk) = self.stride(action);
+        self.spaces[player] += spaces;
+        self.risks[player] = (self.risks[player] + risk).max(0);
+        true
+    }
+
+    fn shares_space(&self, player: usize) -> bool {
+        let space = self.spaces[player] % LOOP_SPACES;
+        (0..3).any(|other| other != player && self.spaces[other] % LOOP_SPACES == space)
+    }
+
+    // collisions and stuns, once everyone moved
+    fn settle(&mut self, moved: [bool; 3]) {
+        let collided = [0, 1, 2].map(|p| moved[p] && self.shares_space(p));
+        for player in 0..3 {
+            if collided[player] {
+                self.risks[player] += COLLISION_RISK;
+            }
+            if moved[player] && self.risks[player] >= MAX_RISK {
+                self.risks[player] = -STUN_TURNS;
+            }
+        }
+        self.turns_left -= 1;
+    }
+
+    /// Spaces travelled counting the stunned turns as lost ones.
+    pub fn effective_spaces(&self, player: usize) -> i32 {
+        self.spaces[player] + self.risks[player].min(0) * 2
+    }
+}
+
+impl MiniGame for Roller {
+    /// reg_0..reg_2 are spaces travelled, reg_3..reg_5 risk or stun, reg_6 turns left.
+    fn parse(gpu: &str, regs: [i32; 7]) -> Option<Self> {
+        let spaces = [regs[0], regs[1], regs[2]];
+        let risks = [regs[3], regs[4], regs[5]];
+        Self::matches(gpu, regs).then(|| Roller::new(gpu, spaces, risks, regs[6]))
+    }
+
+    /// The next risk order is unknown, the current one is kept.
+    fn play(&self, actions: [Action; 3]) -> Self {
+        let mut next = self.clone();
+        let mut moved = [false; 3];
+        for (player, action) in actions.iter().enumerate() {
+            moved[player] = next.move_player(player, *action);
+        }
+        next.settle(moved);
+        next
+    }
+
+    fn after_move(&self, player: usize, action: Action) -> Self {
+        let mut next = self.clone();
+        let mut moved = [false; 3];
+        moved[player] = next.move_player(player, action);
+        next.settle(moved);
+        next
+    }
+
+    fn is_over(&self) -> bool {
+        self.turns_left <= 0
+    }
+
+    /// Most spaces travelled places best.
+    fn ranking(&self) -> [usize; 3] {
+        rank_by(self.spaces)
+    }
+
+    /// Spaces first, the lower risk breaking ties.
+    fn progress(&self, player: usize) -> i32 {
+        self.effective_spaces(player) * MAX_RISK - self.risks[player].max(0)
+    }
+
+    fn gpu(&self) -> String {
+        self.risk_order.clone()
+    }
+
+    fn registers(&self) -> [i32; 7] {
+        let [s0, s1, s2] = self.spaces;
+        let [r0, r1, r2] = self.risks;
+        [s0, s1, s2, r0, r1, r2, self.turns_left]
+    }
+}
+
+#[cfg(test)]
+mod test_roller {
+    use super::*;
+    use crate::action::Action::*;
+
+    #[test]
+    fn parse_registers() {
+        let regs = [4, 7, 2, 1, -2, 0, 12];
+        assert!(Roller::matches("ULDR", regs));
+        assert!(!Roller::matches("ULDR", [0, 0, 0, 0, 0, 0, -1]));
+        assert!(!Roller::matches("UULR", regs));
+
+        let roller = Roller::parse("ULDR", regs).unwrap();
+        assert_eq!(roller.spaces(), [4, 7, 2]);
+        assert_eq!(roller.risks(), [1, -2, 0]);
+        assert!(roller.is_stunned(1));
+        assert_eq!(roller.turns_left(), 12);
+    }
+
+    #[test]
+    fn risk_order_sets_speed_and_risk() {
+        let roller = Roller::new("ULDR", [0, 10, 20], [2, 2, 2], 5);
+        let next = roller.play([Up, Left, Right]);
+
+        // 1, 2 and 3 spaces: no one shares a space
+        assert_eq!(next.spaces(), [1, 12, 23]);
+        assert_eq!(next.risks(), [1, 2, 4]);
+        assert_eq!(next.turns_left(), 4);
+    }
+
+    #[test]
+    fn sharing_space_adds_risk() {
+        let roller = Roller::new("ULDR", [0, 11, 5], [0, 0, 0], 5);
+        let next = roller.play([Left, Left, Up]);
+        assert_eq!(next.spaces(), [2, 13, 6]);
+        assert_eq!(next.risks(), [0, 0, 0]);
+
+        // 2 and 12 are the same space on the loop
+        let next = roller.play([Down, Up, Up]);
+        assert_eq!(next.spaces(), [2, 12, 6]);
+        assert_eq!(next.risks(), [3, 2, 0]);
+    }
+
+    #[test]
+    fn stunned_at_max_risk() {
+        let roller = Roller::new("ULDR", [0, 5, 8], [3, 0, 0], 4);
+        let mut next = roller.after_move(0, Right);
+        assert_eq!(next.risks()[0], -STUN_TURNS);
+        assert_eq!(next.effective_spaces(0), 3 - 2 * STUN_TURNS);
+
+        for _ in 0..STUN_TURNS {
+            next = next.play([Right, Up, Up]);
+            assert_eq!(next.spaces()[0], 3);
+        }
+        assert_eq!(next.risks()[0], 0);
+        assert!(!next.is_over());
+
+        next = next.play([Up, Up, Up]);
+        assert!(next.is_over());
+        assert_eq!(next.ranking(), [2, 1, 0]);
+    }
+}
@@ -0,0 +1,185 @@
+// Medal scoring:
+// - each mini-game scores gold * 3 + silver for a player
+// - the final score is the product of the four mini-game scores
+// - the score line is the final score, then gold silver bronze for each mini-game
+
+use std::fmt;
+
+pub const NB_MINI_GAMES: usize = 4;
+// points of gold, silver and bronze
+const MEDAL_POINTS: [u32; 3] = [3, 1, 0];
+
+/// Mini-game points of the medal for this placement, 0 being gold.
+pub fn placement_points(placement: usize) -> u32 {
+    MEDAL_POINTS[placement.min(2)]
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MiniGameScore {
+    pub gold: u32,
+    pub silver: u32,
+    pub bronze: u32,
+}
+
+impl MiniGameScore {
+    pub fn new(gold: u32, silver: u32, bronze: u32) -> Self {
+        MiniGameScore {
+            gold,
+            silver,
+            bronze,
+        }
+    }
+
+    pub fn points(&self) -> u32 {
+        self.gold * 3 + self.silver
+    }
+
+    /// Adds the medal for this placement, 0 being gold.
+    pub fn add_medal(&mut self, placement: usize) {
+        match placement {
+            0 => self.gold += 1,
+            1 => self.silver += 1,
+            _ => self.bronze += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerScore {
+    // final score as reported by the referee
+    pub final_score: u32,
+    pub games: [MiniGameScore; NB_MINI_GAMES],
+}
+
+impl PlayerScore {
+    pub fn new(games: [MiniGameScore; NB_MINI_GAMES]) -> Self {
+        let mut score = PlayerScore {
+            final_score: 0,
+            games,
+        };
+        score.final_score = score.calculate_final_score();
+        score
+    }
+
+    /// Reads "final_score gold silver bronze ..." with one medal triple per mini-game.
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields = line
+            .split_whitespace()
+            .map(|field| field.parse::<u32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let (&final_score, medals) = fields.split_first()?;
+        if medals.len() != NB_MINI_GAMES * 3 {
+            return None;
+        }
+
+        let mut games = [MiniGameScore::default(); NB_MINI_GAMES];
+        for (game, triple) in games.iter_mut().zip(medals.chunks(3)) {
+            *game = MiniGameScore::new(triple[0], triple[1], triple[2]);
+        }
+        Some(PlayerScore { final_score, games })
+    }
+
+    pub fn gold(&self) -> u32 {
+        self.games.iter().map(|game| game.gold).sum()
+    }
+
+    pub fn silver(&self) -> u32 {
+        self.games.iter().map(|game| game.silver).sum()
+    }
+
+    pub fn bronze(&self) -> u32 {
+        self.games.iter().map(|game| game.bronze).sum()
+    }
+
+    pub fn calculate_final_score(&self) -> u32 {
+        self.games.iter().map(MiniGameScore::points).product()
+    }
+
+    pub fn add_medal(&mut self, game: usize, placement: usize) {
+        self.games[game].add_medal(placement);
+        self.final_score = self.calculate_final_score();
+    }
+
+    /// Score after one more medal of this placement in mini-game `game`.
+    pub fn with_medal(&self, game: usize, placement: usize) -> Self {
+        let mut score = self.clone();
+        score.add_medal(game, placement);
+        score
+    }
+
+    /// How much the final score changes with this medal in mini-game `game`.
+    pub fn medal_gain(&self, game: usize, placement: usize) -> i64 {
+        let after = self.with_medal(game, placement).calculate_final_score();
+        after as i64 - self.calculate_final_score() as i64
+    }
+
+    pub fn gold_gain(&self, game: usize) -> i64 {
+        self.medal_gain(game, 0)
+    }
+}
+
+// the score line, as parsed by `PlayerScore::parse`
+impl fmt::Display for PlayerScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.final_score)?;
+        for game in &self.games {
+            write!(f, " {} {} {}", game.gold, game.silver, game.bronze)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_scoring {
+    use super::*;
+
+    #[test]
+    fn parse_score_line() {
+        let score = PlayerScore::parse("40 2 1 0 1 0 3 0 1 2 1 1 1\n").unwrap();
+
+        assert_eq!(score.final_score, 40);
+        assert_eq!(score.games[0], MiniGameScore::new(2, 1, 0));
+        assert_eq!(score.games[3], MiniGameScore::new(1, 1, 1));
+        assert_eq!((score.gold(), score.silver(), score.bronze()), (4, 3, 6));
+        assert_eq!(score.calculate_final_score(), 84);
+
+        assert_eq!(score.to_string(), "40 2 1 0 1 0 3 0 1 2 1 1 1");
+        assert_eq!(PlayerScore::parse("0 1 2 3"), None);
+        assert_eq!(PlayerScore::parse("0 1 2 3 1 2 3 1 2 3 1 2 x"), None);
+    }
+
+    #[test]
+    fn final_score_is_product() {
+        let mut score = PlayerScore::default();
+        assert_eq!(score.calculate_final_score(), 0);
+
+        for game in 0..NB_MINI_GAMES {
+            score.add_medal(game, 1);
+        }
+        assert_eq!(score.final_score, 1);
+
+        // bronze adds no points
+        score.add_medal(2, 2);
+        assert_eq!(score.final_score, 1);
+        score.add_medal(2, 0);
+        assert_eq!(score.final_score, 4);
+    }
+
+    #[test]
+    fn gold_gain_is_largest_in_weakest_game() {
+        let games = [
+            MiniGameScore::new(3, 0, 0),
+            MiniGameScore::new(0, 1, 2),
+            MiniGameScore::new(1, 0, 0),
+            MiniGameScore::new(1, 1, 0),
+        ];
+        let score = PlayerScore::new(games);
+        assert_eq!(score.final_score, 108);
+
+        // 12 * 1 * 3 * 4 against 9 * 4 * 3 * 4
+        assert_eq!(score.gold_gain(0), 144 - 108);
+        assert_eq!(score.gold_gain(1), 432 - 108);
+        assert_eq!(score.medal_gain(1, 2), 0);
+        assert_eq!(placement_points(0), MiniGameScore::new(1, 0, 0).points());
+    }
+}
@@ -0,0 +1,89 @@
+// Per-turn time budget:
+// - the first turn allows 1s to answer, every other turn 50ms, counted from the turn input
+// - the searches keeping state between turns spend the first turn growing it: the MCTS tree
+//   and the planner population carry over to the turns after
+// - a safety margin is kept for parsing, printing and the scheduler
+// - searches poll `is_over` between iterations and play their best action so far
+
+use std::time::{Duration, Instant};
+
+pub const FIRST_TURN_LIMIT: Duration = Duration::from_millis(1000);
+pub const TURN_LIMIT: Duration = Duration::from_millis(50);
+pub const SAFETY_MARGIN: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    start: Instant,
+    limit: Duration,
+}
+
+impl Budget {
+    /// Budget of `limit`, starting now.
+    pub fn new(limit: Duration) -> Self {
+        Budget {
+            start: Instant::now(),
+            limit,
+        }
+    }
+
+    /// A day, so that the searches in tests stop at their own caps, never at the clock.
+    #[cfg(test)]
+    pub fn unlimited() -> Self {
+        Budget::new(Duration::from_secs(24 * 60 * 60))
+    }
+
+    /// Budget of a turn whose input just arrived, the margin taken off.
+    pub fn for_turn(first_turn: bool) -> Self {
+        let limit = if first_turn {
+            FIRST_TURN_LIMIT
+        } else {
+            TURN_LIMIT
+        };
+        Budget::new(limit.saturating_sub(SAFETY_MARGIN))
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.limit.saturating_sub(self.elapsed())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.elapsed() >= self.limit
+    }
+
+    /// Budget for a step that must leave time for the rest of the turn:
+    /// this fraction of the remaining time, starting now.
+    pub fn share(&self, fraction: f64) -> Budget {
+        Budget::new(self.remaining().mul_f64(fraction.clamp(0.0, 1.0)))
+    }
+}
+
+#[cfg(test)]
+mod test_timer {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn first_turn_is_longer() {
+        let first = Budget::for_turn(true);
+        let next = Budget::for_turn(false);
+        assert!(first.remaining() > Duration::from_millis(900));
+        assert!(next.remaining() <= TURN_LIMIT - SAFETY_MARGIN);
+        assert!(!next.is_over());
+    }
+
+    #[test]
+    fn runs_out() {
+        let budget = Budget::new(Duration::from_millis(5));
+        let half = budget.share(0.5);
+        assert!(half.remaining() <= Duration::from_millis(3));
+
+        thread::sleep(Duration::from_millis(6));
+        assert!(budget.is_over() && half.is_over());
+        assert_eq!(budget.remaining(), Duration::ZERO);
+        assert!(budget.share(1.0).is_over());
+    }
+}
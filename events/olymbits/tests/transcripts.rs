@@ -0,0 +1,52 @@
+// Golden tests: every transcript of tests/transcripts is played by the bot, and its actions
+// compared to the `.out` file next to it. `UPDATE_TRANSCRIPTS=1 cargo test` accepts new actions.
+// Only deterministic strategies can be recorded: the searches depend on the clock.
+// New transcripts come from `referee --record`.
+
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+use transcript::{check, transcripts, Transcript};
+
+fn play(transcript: &Transcript) -> String {
+    let mut bot = Command::new(env!("CARGO_BIN_EXE_olymbits"))
+        .args(&transcript.args)
+        .env("BOT_LOG", "off")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("cannot start the bot");
+    bot.stdin
+        .take()
+        .unwrap()
+        .write_all(transcript.input().as_bytes())
+        .unwrap();
+    let output = bot.wait_with_output().unwrap();
+    assert!(output.status.success(), "the bot failed: {}", output.status);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn recorded_matches() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/transcripts");
+    let paths = transcripts(&dir);
+    assert!(!paths.is_empty());
+
+    let mut failures = Vec::new();
+    for path in paths {
+        let transcript = Transcript::load(&path);
+        let output = play(&transcript);
+        assert_eq!(
+            output.lines().count(),
+            transcript.turns(),
+            "{}",
+            path.display()
+        );
+        if let Err(err) = check(&path, &output) {
+            failures.push(err);
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
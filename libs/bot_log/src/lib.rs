@@ -0,0 +1,270 @@
+// Stderr logging shared by the bots:
+// - five levels, from error to trace, written as "[LEVEL module] message"
+// - BOT_LOG picks the levels, for everything or per module: "warn,olymbits::mcts=debug"
+// - the `off` feature compiles every log call away, for submission
+// - `turn_dump!` writes one "#turn" line per turn, which `TurnDump::parse` reads back
+
+use std::{fmt, str::FromStr, sync::OnceLock};
+
+/// False with the `off` feature: log calls then compile to nothing.
+pub const ENABLED: bool = !cfg!(feature = "off");
+pub const ENV_VAR: &str = "BOT_LOG";
+pub const DEFAULT_LEVEL: Level = Level::Info;
+pub const DUMP_PREFIX: &str = "#turn";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Off => "OFF",
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Level::Off),
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            other => Err(format!("unknown log level `{other}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Level,
+    // module path prefix and its level
+    modules: Vec<(String, Level)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter {
+            default: DEFAULT_LEVEL,
+            modules: Vec::new(),
+        }
+    }
+}
+
+impl Filter {
+    /// Reads comma-separated directives, a bare level setting the default,
+    /// unknown levels being skipped.
+    pub fn parse(spec: &str) -> Self {
+        let mut filter = Filter::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    if let Ok(level) = level.parse() {
+                        filter.modules.push((module.trim().to_string(), level));
+                    }
+                }
+                None => {
+                    if let Ok(level) = directive.parse() {
+                        filter.default = level;
+                    }
+                }
+            }
+        }
+        filter
+    }
+
+    /// Level of the longest module directive matching whole path segments.
+    pub fn level_for(&self, module: &str) -> Level {
+        self.modules
+            .iter()
+            .filter(|(prefix, _)| {
+                module == prefix
+                    || module
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    pub fn enabled(&self, level: Level, module: &str) -> bool {
+        level != Level::Off && level <= self.level_for(module)
+    }
+}
+
+/// Filter from BOT_LOG, read once.
+pub fn filter() -> &'static Filter {
+    static FILTER: OnceLock<Filter> = OnceLock::new();
+    FILTER.get_or_init(|| {
+        std::env::var(ENV_VAR)
+            .map(|spec| Filter::parse(&spec))
+            .unwrap_or_default()
+    })
+}
+
+pub fn enabled(level: Level, module: &str) -> bool {
+    ENABLED && filter().enabled(level, module)
+}
+
+#[doc(hidden)]
+pub fn write(level: Level, module: &str, args: fmt::Arguments) {
+    eprintln!("[{} {}] {}", level.as_str(), module, args);
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::enabled($level, module_path!()) {
+            $crate::write($level, module_path!(), format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::log!($crate::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::log!($crate::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::Level::Trace, $($arg)+) };
+}
+
+/// Writes the turn dump line at info level: `turn_dump!(turn, action = "UP", score = 42)`.
+#[macro_export]
+macro_rules! turn_dump {
+    ($turn:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::enabled($crate::Level::Info, module_path!()) {
+            let dump = $crate::TurnDump::new($turn)$(.field(stringify!($key), $value))*;
+            eprintln!("{}", dump);
+        }
+    };
+}
+
+/// One line of state per turn: "#turn 12 action=UP scores=3,0,1".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TurnDump {
+    turn: usize,
+    fields: Vec<(String, String)>,
+}
+
+impl TurnDump {
+    pub fn new(turn: usize) -> Self {
+        TurnDump {
+            turn,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a field, whitespace in the value turning into commas to keep one token.
+    pub fn field(mut self, key: &str, value: impl fmt::Display) -> Self {
+        let value = value.to_string();
+        let value = value.split_whitespace().collect::<Vec<_>>().join(",");
+        self.fields.push((key.to_string(), value));
+        self
+    }
+
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    /// Reads a dump line back, None for any other stderr line.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut tokens = line.split_whitespace();
+        if tokens.next()? != DUMP_PREFIX {
+            return None;
+        }
+        let mut dump = TurnDump::new(tokens.next()?.parse().ok()?);
+        for token in tokens {
+            let (key, value) = token.split_once('=')?;
+            dump.fields.push((key.to_string(), value.to_string()));
+        }
+        Some(dump)
+    }
+}
+
+impl fmt::Display for TurnDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", DUMP_PREFIX, self.turn)?;
+        for (key, value) in &self.fields {
+            write!(f, " {key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_bot_log {
+    use super::*;
+
+    #[test]
+    fn filter_by_module() {
+        let filter = Filter::parse("warn, olymbits::mcts=debug,olymbits::mcts::tree=off,x=loud");
+
+        assert!(filter.enabled(Level::Warn, "olymbits"));
+        assert!(!filter.enabled(Level::Info, "olymbits::monte_carlo"));
+        assert!(filter.enabled(Level::Debug, "olymbits::mcts"));
+        assert!(!filter.enabled(Level::Error, "olymbits::mcts::tree"));
+        // whole segments only
+        assert_eq!(filter.level_for("olymbits::mcts_old"), Level::Warn);
+        assert_eq!(filter.level_for("x"), Level::Warn);
+        assert_eq!(Filter::parse(""), Filter::default());
+    }
+
+    #[test]
+    fn dump_round_trip() {
+        let dump = TurnDump::new(12)
+            .field("action", "UP")
+            .field("scores", "3 0 1");
+        let line = dump.to_string();
+        assert_eq!(line, "#turn 12 action=UP scores=3,0,1");
+
+        let parsed = TurnDump::parse(&line).unwrap();
+        assert_eq!(parsed, dump);
+        assert_eq!(parsed.get("scores"), Some("3,0,1"));
+        assert_eq!(TurnDump::parse("[INFO olymbits] #turn 3"), None);
+        assert_eq!(TurnDump::parse("#turn x"), None);
+    }
+}
@@ -0,0 +1,246 @@
+// Line-based reader for the game protocols:
+// - lines are numbered from 1, in the order they are read
+// - fields are split on whitespace, so trailing spaces and line endings do not matter
+// - every failure names the line and the field, to validate transcripts offline
+// - a bot stops on its first failure, with exit code 0 when the input closes
+
+use std::{
+    fmt,
+    io::{self, BufRead, StdinLock},
+    process,
+    str::{FromStr, SplitWhitespace},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    EndOfInput,
+    Io(String),
+    MissingField,
+    ExtraField(String),
+    Invalid { value: String, reason: String },
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::EndOfInput => write!(f, "end of input"),
+            ErrorKind::Io(err) => write!(f, "I/O error: {err}"),
+            ErrorKind::MissingField => write!(f, "missing"),
+            ErrorKind::ExtraField(value) => write!(f, "unexpected {value:?}"),
+            ErrorKind::Invalid { value, reason } => write!(f, "invalid {value:?}: {reason}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number, from 1.
+    pub line: usize,
+    /// Field or, for a missing line, line being read.
+    pub field: String,
+    pub kind: ErrorKind,
+}
+
+impl ParseError {
+    pub fn new(line: usize, field: &str, kind: ErrorKind) -> Self {
+        ParseError {
+            line,
+            field: field.to_string(),
+            kind,
+        }
+    }
+
+    /// The input closed: the end of the match rather than a broken line.
+    pub fn is_end_of_input(&self) -> bool {
+        self.kind == ErrorKind::EndOfInput
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, {}: {}", self.line, self.field, self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The input closing ends the game, any other error is a protocol mismatch.
+pub fn read_or_exit<T>(result: Result<T, ParseError>) -> T {
+    result.unwrap_or_else(|err| {
+        if err.is_end_of_input() {
+            bot_log::info!("input closed, {err}");
+            process::exit(0);
+        }
+        bot_log::error!("{err}");
+        process::exit(1);
+    })
+}
+
+pub struct LineReader<R> {
+    input: R,
+    // lines read so far
+    line: usize,
+}
+
+impl LineReader<StdinLock<'static>> {
+    pub fn stdin() -> Self {
+        LineReader::new(io::stdin().lock())
+    }
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(input: R) -> Self {
+        LineReader { input, line: 0 }
+    }
+
+    /// Number of the last line read.
+    pub fn line_number(&self) -> usize {
+        self.line
+    }
+
+    /// Reads the next line, `what` naming it if the input ends.
+    pub fn next_line(&mut self, what: &str) -> Result<Line, ParseError> {
+        let mut text = String::new();
+        let number = self.line + 1;
+        match self.input.read_line(&mut text) {
+            Ok(0) => Err(ParseError::new(number, what, ErrorKind::EndOfInput)),
+            Ok(_) => {
+                self.line = number;
+                Ok(Line { number, text })
+            }
+            Err(err) => Err(ParseError::new(
+                number,
+                what,
+                ErrorKind::Io(err.to_string()),
+            )),
+        }
+    }
+
+    /// Reads a line holding a single field.
+    pub fn next_value<T>(&mut self, name: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let line = self.next_line(name)?;
+        let mut fields = line.fields();
+        let value = fields.next(name)?;
+        fields.end()?;
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    number: usize,
+    text: String,
+}
+
+impl Line {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.trim_end_matches(['\n', '\r'])
+    }
+
+    pub fn fields(&self) -> Fields<'_> {
+        Fields {
+            line: self.number,
+            tokens: self.text.split_whitespace(),
+        }
+    }
+
+    pub fn error(&self, field: &str, kind: ErrorKind) -> ParseError {
+        ParseError::new(self.number, field, kind)
+    }
+}
+
+/// Whitespace-separated fields of a line, read in order.
+pub struct Fields<'a> {
+    line: usize,
+    tokens: SplitWhitespace<'a>,
+}
+
+impl<'a> Fields<'a> {
+    pub fn next_str(&mut self, name: &str) -> Result<&'a str, ParseError> {
+        self.tokens
+            .next()
+            .ok_or_else(|| ParseError::new(self.line, name, ErrorKind::MissingField))
+    }
+
+    pub fn next<T>(&mut self, name: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.next_str(name)?;
+        value.parse().map_err(|err: T::Err| {
+            let kind = ErrorKind::Invalid {
+                value: value.to_string(),
+                reason: err.to_string(),
+            };
+            ParseError::new(self.line, name, kind)
+        })
+    }
+
+    /// Fails on a field left over.
+    pub fn end(mut self) -> Result<(), ParseError> {
+        match self.tokens.next() {
+            None => Ok(()),
+            Some(value) => {
+                let kind = ErrorKind::ExtraField(value.to_string());
+                Err(ParseError::new(self.line, "end of line", kind))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_line_reader {
+    use super::*;
+
+    #[test]
+    fn reads_numbered_fields() {
+        let mut reader = LineReader::new("7 8 \r\n12\nAB\n".as_bytes());
+
+        let line = reader.next_line("position").unwrap();
+        let mut fields = line.fields();
+        assert_eq!(fields.next::<i32>("x"), Ok(7));
+        assert_eq!(fields.next::<i32>("y"), Ok(8));
+        assert_eq!(fields.end(), Ok(()));
+        assert_eq!(line.text(), "7 8 ");
+
+        assert_eq!(reader.next_value::<u32>("turns"), Ok(12));
+        assert_eq!(reader.line_number(), 2);
+        assert_eq!(reader.next_line("hint").unwrap().text(), "AB");
+        let end = reader.next_line("hint").unwrap_err();
+        assert!(end.is_end_of_input());
+        assert_eq!(end.to_string(), "line 4, hint: end of input");
+    }
+
+    #[test]
+    fn errors_name_line_and_field() {
+        let mut reader = LineReader::new("1\n2 x\n3 4 5\n".as_bytes());
+        reader.next_line("skipped").unwrap();
+
+        let line = reader.next_line("position").unwrap();
+        let mut fields = line.fields();
+        fields.next::<i32>("x").unwrap();
+        let err = fields.next::<i32>("y").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(
+            err.to_string(),
+            "line 2, y: invalid \"x\": invalid digit found in string"
+        );
+        assert_eq!(
+            fields.next::<i32>("z").unwrap_err().kind,
+            ErrorKind::MissingField
+        );
+
+        let err = reader.next_value::<i32>("turns").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::ExtraField("4".to_string()));
+        assert!(!err.is_end_of_input());
+    }
+}
@@ -0,0 +1,180 @@
+// Recorded transcripts for golden tests of the bots:
+// - a transcript is the stdin of one bot: the init block, then one block per turn,
+//   blocks separated by a blank line
+// - "# args: ..." gives the bot arguments, other lines starting with '#' are comments
+// - the bot output, one line per turn, is compared to the stored `.out` file,
+//   or written to it when UPDATE_TRANSCRIPTS is set, for the change to show in review
+
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+pub const UPDATE_VAR: &str = "UPDATE_TRANSCRIPTS";
+const ARGS_PREFIX: &str = "# args:";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub args: Vec<String>,
+    pub comments: Vec<String>,
+    // the init block first
+    pub blocks: Vec<Vec<String>>,
+}
+
+impl Transcript {
+    pub fn new(args: &[&str]) -> Self {
+        Transcript {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn comment(&mut self, comment: &str) {
+        self.comments.push(comment.to_string());
+    }
+
+    pub fn push_block(&mut self, lines: &[String]) {
+        self.blocks.push(lines.to_vec());
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut transcript = Transcript::default();
+        let mut block = Vec::new();
+        for line in text.lines().map(str::trim_end) {
+            if let Some(args) = line.strip_prefix(ARGS_PREFIX) {
+                transcript.args = args.split_whitespace().map(str::to_string).collect();
+            } else if let Some(comment) = line.strip_prefix('#') {
+                transcript.comments.push(comment.trim().to_string());
+            } else if line.is_empty() {
+                if !block.is_empty() {
+                    transcript.blocks.push(std::mem::take(&mut block));
+                }
+            } else {
+                block.push(line.to_string());
+            }
+        }
+        if !block.is_empty() {
+            transcript.blocks.push(block);
+        }
+        transcript
+    }
+
+    pub fn load(path: &Path) -> Self {
+        let text = fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("cannot read {}: {err}", path.display()));
+        Transcript::parse(&text)
+    }
+
+    /// Turns played, the init block left out.
+    pub fn turns(&self) -> usize {
+        self.blocks.len().saturating_sub(1)
+    }
+
+    /// The bot stdin, without blank lines or comments.
+    pub fn input(&self) -> String {
+        self.blocks
+            .iter()
+            .flatten()
+            .map(|line| format!("{line}\n"))
+            .collect()
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.args.is_empty() {
+            writeln!(f, "{ARGS_PREFIX} {}", self.args.join(" "))?;
+        }
+        for comment in &self.comments {
+            writeln!(f, "# {comment}")?;
+        }
+        for block in &self.blocks {
+            writeln!(f)?;
+            for line in block {
+                writeln!(f, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Transcripts of the directory, `.in` files sorted by name.
+pub fn transcripts(dir: &Path) -> Vec<PathBuf> {
+    let entries =
+        fs::read_dir(dir).unwrap_or_else(|err| panic!("cannot list {}: {err}", dir.display()));
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "in"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Compares the output to the `.out` file next to the transcript, or stores it in update mode.
+pub fn check(transcript: &Path, output: &str) -> Result<(), String> {
+    let expected_path = transcript.with_extension("out");
+    if env::var_os(UPDATE_VAR).is_some() {
+        return fs::write(&expected_path, output)
+            .map_err(|err| format!("cannot write {}: {err}", expected_path.display()));
+    }
+    let expected = fs::read_to_string(&expected_path).map_err(|err| {
+        format!(
+            "cannot read {}: {err}, run with {UPDATE_VAR}=1 to create it",
+            expected_path.display()
+        )
+    })?;
+    match first_difference(&expected, output) {
+        None => Ok(()),
+        Some((line, want, got)) => Err(format!(
+            "{}: line {line} expected {want:?}, got {got:?}, run with {UPDATE_VAR}=1 to accept",
+            transcript.display()
+        )),
+    }
+}
+
+// 1-based line number and both lines, "" past the end
+fn first_difference<'a>(expected: &'a str, actual: &'a str) -> Option<(usize, &'a str, &'a str)> {
+    let (mut expected, mut actual) = (expected.lines(), actual.lines());
+    for line in 1.. {
+        match (expected.next(), actual.next()) {
+            (None, None) => return None,
+            (want, got) if want != got => {
+                return Some((line, want.unwrap_or(""), got.unwrap_or("")))
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test_transcript {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "# args: safe\n# seed 3\n\n1\n4\n\nUR\n\n\nD \n";
+        let transcript = Transcript::parse(text);
+        assert_eq!(transcript.args, vec!["safe"]);
+        assert_eq!(transcript.comments, vec!["seed 3"]);
+        assert_eq!(transcript.turns(), 2);
+        assert_eq!(transcript.input(), "1\n4\nUR\nD\n");
+
+        let mut recorded = Transcript::new(&["safe"]);
+        recorded.comment("seed 3");
+        for block in &transcript.blocks {
+            recorded.push_block(block);
+        }
+        assert_eq!(Transcript::parse(&recorded.to_string()), transcript);
+    }
+
+    #[test]
+    fn finds_first_difference() {
+        assert_eq!(first_difference("UP\nLEFT\n", "UP\nLEFT"), None);
+        assert_eq!(
+            first_difference("UP\nLEFT\n", "UP\nDOWN\n"),
+            Some((2, "LEFT", "DOWN"))
+        );
+        assert_eq!(first_difference("UP\n", "UP\nUP\n"), Some((2, "", "UP")));
+    }
+}